@@ -0,0 +1,23 @@
+//! Support code for the `#[synchronized]` attribute.
+
+/// Key of the sync point shared by all methods of a type (`(Monitor, Self)`)
+/// in `core::registry`.
+pub struct Monitor;
+
+/// Executes the body of a synchronized function.
+///
+/// `return` and `?` inside the body only leave the closure,
+/// so the lock is released by the code following it in `sync!`.
+#[inline(always)]
+pub fn call<R>(body: impl FnOnce() -> R) -> R {
+	body()
+}
+
+/// Executes the body of a synchronized `async fn`.
+///
+/// `return` and `?` inside the body only leave the closure,
+/// so the lock is released by the code following it in `sync!`.
+#[inline(always)]
+pub async fn call_async<R>(body: impl AsyncFnOnce() -> R) -> R {
+	body().await
+}
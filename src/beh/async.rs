@@ -1,12 +1,19 @@
 //! Synchronization primitive for the `synchronized`
 //! macro implemented by the `tokio`+`parking_lot` library.
 
+extern crate std;
 extern crate tokio;
 
+use crate::cfg::cfg_async;
 use crate::core::SyncPointBeh;
+use crate::core::SyncPointCapabilities;
 use crate::core::r#async::cfg_async_or_sync;
 pub use tokio::sync::Mutex;
 pub use tokio::sync::MutexGuard;
+use tokio::sync::OnceCell;
+pub use tokio::sync::RwLock;
+pub use tokio::sync::RwLockReadGuard;
+pub use tokio::sync::RwLockWriteGuard;
 
 cfg_async_or_sync! {
 	impl[T: Send] SyncPointBeh for Mutex<T> {
@@ -17,6 +24,18 @@ cfg_async_or_sync! {
 			fn new_lock(&self) -> impl core::future::Future<Output = Self::LockType<'_>> + Send {
 				Mutex::lock(self)
 			}
+
+			#[inline]
+			async fn try_lock_for(&self, timeout: core::time::Duration) -> Option<Self::LockType<'_>> {
+				tokio::time::timeout(timeout, Mutex::lock(self)).await.ok()
+			}
+
+			#[inline]
+			async fn try_lock_until(&self, deadline: std::time::Instant) -> Option<Self::LockType<'_>> {
+				tokio::time::timeout_at(deadline.into(), Mutex::lock(self))
+					.await
+					.ok()
+			}
 		}
 		/// This section of code is connected only if
 		/// the current library is synchronous.
@@ -29,6 +48,14 @@ cfg_async_or_sync! {
 		type LockType<'a> = MutexGuard<'a, T> where T: 'a;
 		type DerefLockType = T;
 
+		const CAPABILITIES: SyncPointCapabilities = SyncPointCapabilities {
+			timed_lock: true,
+			try_lock: true,
+			fair: true,
+			is_async: cfg!(not(any(feature = "pl", feature = "std"))),
+			..SyncPointCapabilities::NONE
+		};
+
 		#[inline]
 		fn try_lock(&self) -> Option<Self::LockType<'_>> {
 			Mutex::try_lock(self).ok()
@@ -41,18 +68,158 @@ cfg_async_or_sync! {
 	}
 }
 
+cfg_async! {
+	/// Only in the asynchronous version of the library, `tokio` locks are awaited.
+	impl<T: Send + Sync> crate::core::RwSyncPointBeh for RwLock<T> {
+		type WriteLockType<'a> = RwLockWriteGuard<'a, T> where T: 'a;
+		type ReadLockType<'a> = RwLockReadGuard<'a, T> where T: 'a;
+		type DerefLockType = T;
+
+		#[inline]
+		fn write(&self) -> impl core::future::Future<Output = Self::WriteLockType<'_>> + Send {
+			RwLock::write(self)
+		}
+
+		#[inline]
+		fn read(&self) -> impl core::future::Future<Output = Self::ReadLockType<'_>> + Send {
+			RwLock::read(self)
+		}
+
+		#[inline]
+		fn try_write(&self) -> Option<Self::WriteLockType<'_>> {
+			RwLock::try_write(self).ok()
+		}
+
+		#[inline]
+		fn try_read(&self) -> Option<Self::ReadLockType<'_>> {
+			RwLock::try_read(self).ok()
+		}
+
+		#[inline]
+		fn downgrade<'a>(lock: Self::WriteLockType<'a>) -> Self::ReadLockType<'a> where Self: 'a {
+			RwLockWriteGuard::downgrade(lock)
+		}
+	}
+}
+
+/// A `Mutex` whose value is created on first lock,
+/// allows to use non-const expressions as the initial value.
+pub struct LazyMutex<T> {
+	cell: OnceCell<Mutex<T>>,
+	init: fn() -> T,
+}
+
+impl<T> LazyMutex<T> {
+	/// Creation of a structure with a function for creating the value on first lock.
+	#[inline]
+	pub const fn new(init: fn() -> T) -> Self {
+		Self {
+			cell: OnceCell::const_new(),
+			init,
+		}
+	}
+
+	/// Get the already created `Mutex` or create it without waiting,
+	/// returns None if another task is creating it right now.
+	fn try_force(&self) -> Option<&Mutex<T>> {
+		if let Some(a) = self.cell.get() {
+			return Some(a);
+		}
+
+		let _e = self.cell.set(Mutex::new((self.init)()));
+		self.cell.get()
+	}
+}
+
+cfg_async_or_sync! {
+	impl[T: Send] SyncPointBeh for LazyMutex<T> {
+		/// This section of code is connected only if
+		/// the current library is asynchronous.
+		#only_async {
+			#[inline]
+			async fn new_lock(&self) -> Self::LockType<'_> {
+				self.cell
+					.get_or_init(|| async { Mutex::new((self.init)()) })
+					.await
+					.lock()
+					.await
+			}
+
+			#[inline]
+			async fn try_lock_for(&self, timeout: core::time::Duration) -> Option<Self::LockType<'_>> {
+				tokio::time::timeout(timeout, SyncPointBeh::new_lock(self)).await.ok()
+			}
+
+			#[inline]
+			async fn try_lock_until(&self, deadline: std::time::Instant) -> Option<Self::LockType<'_>> {
+				tokio::time::timeout_at(deadline.into(), SyncPointBeh::new_lock(self))
+					.await
+					.ok()
+			}
+		}
+		/// This section of code is connected only if
+		/// the current library is synchronous.
+		#only_sync {
+			#[inline]
+			fn new_lock(&self) -> Self::LockType<'_> {
+				unimplemented!();
+			}
+		}
+		type LockType<'a> = MutexGuard<'a, T> where T: 'a;
+		type DerefLockType = T;
+
+		const CAPABILITIES: SyncPointCapabilities = <Mutex<T> as SyncPointBeh>::CAPABILITIES;
+
+		#[inline]
+		fn try_lock(&self) -> Option<Self::LockType<'_>> {
+			Mutex::try_lock(self.try_force()?).ok()
+		}
+
+		#[inline]
+		fn unlock(&self, lock_type: Self::LockType<'_>) {
+			drop(lock_type)
+		}
+	}
+}
+
 /// An implementation specifying which synchronization to use in synchonized.
 ///
 /// Required for implementation:
 ///
-/// 1. #new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+/// 1. #new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 /// Defining a new synchronization point, usually implements static variables used during synchronization.
-/// 2. #new_lock($lock:ident): $v_point_name:ident
+/// 2. #new_lock($lock:ident): $v_point_name:path
 /// Creates a new lock on an already created sync point (#new_point)
-/// 3. #drop_lock($lock: ident): $v_point_name:ident
+/// 3. #drop_lock($lock: ident): $v_point_name:path
 /// Deletes a newly created lock (#new_lock)
 /// 4. #name
 /// Definition of the current implementation
+/// 5. #capabilities
+/// Capabilities of the synchronization structure of the current implementation
+/// 6. #new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+/// Defining a new synchronization point whose value is created on first lock
+/// 7. #new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+/// Defining a new synchronization point for each key type, stored in `core::registry`
+/// 8. #try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) waiting no longer than `$timeout`, wrapped in `Option`
+/// 9. #lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) until `$token` is cancelled, wrapped in `Result`
+/// 10. #new_lock_all($lock:ident): $v_point_names:expr
+/// Creates new locks on several sync points at once (`SyncPointSet`)
+/// 11. #new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point with a separate lock for each key (`KeyedSyncPoint`)
+/// 12. #new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new table of `$n` sync points to synchronize on object instances (`StripedSyncPoint`)
+/// 13. #wait_while($lock:ident, $condition:expr): $v_point_name:path
+/// Releases the lock and waits for a notification while `$condition` is true (`SyncPoint::wait_while`)
+/// 14. #reset: $v_point_name:path
+/// Replaces the value of an already created sync point (#new_point) with the initial value (`SyncPoint::reset`)
+/// 15. #new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point shared by readers and held exclusively by writers (`RwSyncPoint`)
+/// 16. #new_write_lock($lock:ident): $v_point_name:path
+/// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+/// 17. #new_read_lock($lock:ident): $v_point_name:path
+/// Creates a new shared lock on an already created sync point (#new_rw_point)
 #[macro_export]
 #[doc(hidden)]
 #[cfg(not(any(feature = "pl", feature = "std")))]
@@ -62,33 +229,180 @@ macro_rules! __sync_beh {
 		#name
 	} => { "async(tokio+parking_lot)" };
 
+	{
+		// Capabilities of the synchronization structure of the current implementation
+		#capabilities
+	} => { $crate::core::SyncPoint::<$crate::beh::r#async::Mutex<()>>::CAPABILITIES };
+
 	{
 		// Defining a new synchronization point, usually implements static
 		// variables used during synchronization.
-		#new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+		#new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 	} => {
 
 		/// Generated Synchronization Point
 		#[allow(dead_code)]
 		#[allow(non_upper_case_globals)]
 		#[allow(non_camel_case_types)]
-		pub static $v_point_name: $crate::core::SyncPoint<
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
 			$crate::beh::r#async::Mutex<$t>
 		> = $crate::core::SyncPoint::new($crate::beh::r#async::Mutex::const_new(
 			$t_make
-		));
+		))
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point whose value is created on first lock.
+		#new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
+			$crate::beh::r#async::LazyMutex<$t>
+		> = $crate::core::SyncPoint::new($crate::beh::r#async::LazyMutex::new(
+			|| $t_make
+		))
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point for each key type `$key`,
+		// used where a static variable cannot be declared (generic functions).
+		#new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+	} => {
+		#[allow(non_snake_case)]
+		let $v_point_name: &'static $crate::core::SyncPoint<
+			$crate::beh::r#async::Mutex<$t>
+		> = {
+			static __SYNC_SITE_CACHE: $crate::core::registry::SiteCache = $crate::core::registry::SiteCache::new();
+
+			__SYNC_SITE_CACHE.get_or_init::<$key, _>(|| $crate::core::SyncPoint::new(
+				$crate::beh::r#async::Mutex::new($t_make)
+			).with_init(|| $t_make).with_name(stringify!($v_point_name)))
+			.register()
+		};
+	};
+	{
+		// Defining a new synchronization point with a separate lock for each key of type `$k`.
+		#new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::KeyedSyncPoint<
+			$k,
+			$crate::beh::r#async::Mutex<()>
+		> = $crate::core::KeyedSyncPoint::new();
+	};
+	{
+		// Defining a new table of `$n` synchronization points (stripes)
+		// used to synchronize on object instances.
+		#new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::StripedSyncPoint<
+			$crate::beh::r#async::Mutex<()>,
+			{ $n }
+		> = $crate::core::StripedSyncPoint::new(
+			[const { $crate::core::SyncPoint::new($crate::beh::r#async::Mutex::const_new(())) }; $n]
+		);
+	};
+	{
+		// Defining a new synchronization point shared by readers
+		// and held exclusively by writers.
+		#new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::RwSyncPoint<
+			$crate::beh::r#async::RwLock<$t>
+		> = $crate::core::RwSyncPoint::new($crate::beh::r#async::RwLock::const_new(
+			$t_make
+		))
+		.with_name(stringify!($v_point_name));
 	};
 	{
 		// Creates a new lock on an already created sync point (#new_point)
-		#new_lock($lock:ident): $v_point_name:ident
+		#new_lock($lock:ident): $v_point_name:path
 	} => {
 		#[allow(unused_mut)]
 		let mut $lock = $v_point_name.new_lock().await;
 	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// waiting no longer than `$timeout`, the lock is wrapped in `Option`
+		#try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.try_lock_for($timeout).await;
+	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// abandoning the waiting if `$token` is cancelled, the lock is wrapped in `Result`
+		#lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.lock_cancellable($token).await;
+	};
+	{
+		// Creates new locks on several already created sync points (`SyncPointSet`)
+		#new_lock_all($lock:ident): $v_point_names:expr
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $crate::core::SyncPointSet::lock_all($v_point_names).await;
+	};
+	{
+		// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+		#new_write_lock($lock:ident): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.write().await;
+	};
+	{
+		// Creates a new shared lock on an already created sync point (#new_rw_point)
+		#new_read_lock($lock:ident): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.read().await;
+	};
+	{
+		// Releases the lock (#new_lock) and waits for a notification while `$condition` is true,
+		// the lock is held again after that
+		#wait_while($lock:ident, $condition:expr): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.wait_while($lock, $condition).await;
+	};
+	{
+		// Replaces the value of an already created sync point (#new_point)
+		// with the initial value
+		#reset: $v_point_name:path
+	} => {
+		$crate::core::SyncPoint::reset(&$v_point_name).await
+	};
 	{
 		// Deletes a newly created lock (#new_lock)
-		#drop_lock($lock: ident): $v_point_name:ident
+		#drop_lock($lock: ident): $v_point_name:path
 	} => {
-		$crate::core::SyncPoint::unlock(&$v_point_name, $lock);
+		$v_point_name.unlock($lock);
 	};
 }
@@ -2,11 +2,21 @@
 //! macro implemented by the `parking_lot` library.
 
 extern crate parking_lot;
+extern crate std;
 
+use crate::core::RwSyncPointBeh;
 use crate::core::SyncPointBeh;
+use crate::core::SyncPointCapabilities;
+use core::time::Duration;
 pub use parking_lot::Mutex;
 pub use parking_lot::MutexGuard;
+pub use parking_lot::RwLock;
+pub use parking_lot::RwLockReadGuard;
+pub use parking_lot::RwLockWriteGuard;
 pub use parking_lot::const_mutex;
+pub use parking_lot::const_rwlock;
+pub use std::sync::LazyLock;
+use std::time::Instant;
 
 impl<T> SyncPointBeh for Mutex<T> {
 	type LockType<'a>
@@ -15,11 +25,32 @@ impl<T> SyncPointBeh for Mutex<T> {
 		T: 'a;
 	type DerefLockType = T;
 
+	const CAPABILITIES: SyncPointCapabilities = SyncPointCapabilities {
+		timed_lock: true,
+		try_lock: true,
+		is_lock: cfg!(all(
+			feature = "pl",
+			not(feature = "std"),
+			not(feature = "async")
+		)),
+		..SyncPointCapabilities::NONE
+	};
+
 	#[inline]
 	fn new_lock(&self) -> Self::LockType<'_> {
 		Mutex::lock(self)
 	}
 
+	#[inline]
+	fn try_lock_for(&self, timeout: Duration) -> Option<Self::LockType<'_>> {
+		Mutex::try_lock_for(self, timeout)
+	}
+
+	#[inline]
+	fn try_lock_until(&self, deadline: Instant) -> Option<Self::LockType<'_>> {
+		Mutex::try_lock_until(self, deadline)
+	}
+
 	#[inline]
 	#[cfg_attr(docsrs, doc(cfg(feature = "pl")))]
 	#[cfg(all(feature = "pl", not(feature = "std"), not(feature = "async")))]
@@ -38,18 +69,131 @@ impl<T> SyncPointBeh for Mutex<T> {
 	}
 }
 
+/// Lazy initialization of the synchronized value on first lock,
+/// allows to use non-const expressions as the initial value.
+impl<T, F> SyncPointBeh for LazyLock<Mutex<T>, F>
+where
+	F: FnOnce() -> Mutex<T>,
+{
+	type LockType<'a>
+		= MutexGuard<'a, T>
+	where
+		Self: 'a;
+	type DerefLockType = T;
+
+	const CAPABILITIES: SyncPointCapabilities = <Mutex<T> as SyncPointBeh>::CAPABILITIES;
+
+	#[inline]
+	fn new_lock(&self) -> Self::LockType<'_> {
+		SyncPointBeh::new_lock(LazyLock::force(self))
+	}
+
+	#[inline]
+	fn try_lock_for(&self, timeout: Duration) -> Option<Self::LockType<'_>> {
+		SyncPointBeh::try_lock_for(LazyLock::force(self), timeout)
+	}
+
+	#[inline]
+	fn try_lock_until(&self, deadline: Instant) -> Option<Self::LockType<'_>> {
+		SyncPointBeh::try_lock_until(LazyLock::force(self), deadline)
+	}
+
+	#[inline]
+	#[cfg_attr(docsrs, doc(cfg(feature = "pl")))]
+	#[cfg(all(feature = "pl", not(feature = "std"), not(feature = "async")))]
+	fn is_lock(&self) -> bool {
+		SyncPointBeh::is_lock(LazyLock::force(self))
+	}
+
+	#[inline]
+	fn try_lock(&self) -> Option<Self::LockType<'_>> {
+		SyncPointBeh::try_lock(LazyLock::force(self))
+	}
+
+	#[inline]
+	fn unlock<'a>(&'a self, lock_type: Self::LockType<'a>) {
+		SyncPointBeh::unlock(LazyLock::force(self), lock_type)
+	}
+}
+
+impl<T> RwSyncPointBeh for RwLock<T> {
+	type WriteLockType<'a>
+		= RwLockWriteGuard<'a, T>
+	where
+		T: 'a;
+	type ReadLockType<'a>
+		= RwLockReadGuard<'a, T>
+	where
+		T: 'a;
+	type DerefLockType = T;
+
+	#[inline]
+	fn write(&self) -> Self::WriteLockType<'_> {
+		RwLock::write(self)
+	}
+
+	#[inline]
+	fn read(&self) -> Self::ReadLockType<'_> {
+		RwLock::read(self)
+	}
+
+	#[inline]
+	fn try_write(&self) -> Option<Self::WriteLockType<'_>> {
+		RwLock::try_write(self)
+	}
+
+	#[inline]
+	fn try_read(&self) -> Option<Self::ReadLockType<'_>> {
+		RwLock::try_read(self)
+	}
+
+	#[inline]
+	fn downgrade<'a>(lock: Self::WriteLockType<'a>) -> Self::ReadLockType<'a>
+	where
+		Self: 'a,
+	{
+		RwLockWriteGuard::downgrade(lock)
+	}
+}
+
 /// An implementation specifying which synchronization to use in synchonized.
 ///
 /// Required for implementation:
 ///
-/// 1. #new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+/// 1. #new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 /// Defining a new synchronization point, usually implements static variables used during synchronization.
-/// 2. #new_lock($lock:ident): $v_point_name:ident
+/// 2. #new_lock($lock:ident): $v_point_name:path
 /// Creates a new lock on an already created sync point (#new_point)
-/// 3. #drop_lock($lock: ident): $v_point_name:ident
+/// 3. #drop_lock($lock: ident): $v_point_name:path
 /// Deletes a newly created lock (#new_lock)
 /// 4. #name
 /// Definition of the current implementation
+/// 5. #capabilities
+/// Capabilities of the synchronization structure of the current implementation
+/// 6. #new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+/// Defining a new synchronization point whose value is created on first lock
+/// 7. #new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+/// Defining a new synchronization point for each key type, stored in `core::registry`
+/// 8. #try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) waiting no longer than `$timeout`, wrapped in `Option`
+/// 9. #lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) until `$token` is cancelled, wrapped in `Result`
+/// 10. #new_lock_all($lock:ident): $v_point_names:expr
+/// Creates new locks on several sync points at once (`SyncPointSet`)
+/// 11. #new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point with a separate lock for each key (`KeyedSyncPoint`)
+/// 12. #new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new table of `$n` sync points to synchronize on object instances (`StripedSyncPoint`)
+/// 13. #wait_while($lock:ident, $condition:expr): $v_point_name:path
+/// Releases the lock and waits for a notification while `$condition` is true (`SyncPoint::wait_while`)
+/// 14. #reset: $v_point_name:path
+/// Replaces the value of an already created sync point (#new_point) with the initial value (`SyncPoint::reset`)
+/// 15. #new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point shared by readers and held exclusively by writers (`RwSyncPoint`)
+/// 16. #new_write_lock($lock:ident): $v_point_name:path
+/// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+/// 17. #new_read_lock($lock:ident): $v_point_name:path
+/// Creates a new shared lock on an already created sync point (#new_rw_point)
 #[macro_export]
 #[doc(hidden)]
 #[cfg(not(any(feature = "std", feature = "async")))]
@@ -59,32 +203,181 @@ macro_rules! __sync_beh {
 		#name
 	} => { "parking_lot" };
 
+	{
+		// Capabilities of the synchronization structure of the current implementation
+		#capabilities
+	} => { $crate::core::SyncPoint::<$crate::beh::pl::Mutex<()>>::CAPABILITIES };
+
 	{
 		// Defining a new synchronization point, usually implements static
 		// variables used during synchronization.
-		#new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+		#new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 	} => {
 		/// Generated Synchronization Point
 		#[allow(dead_code)]
 		#[allow(non_upper_case_globals)]
 		#[allow(non_camel_case_types)]
-		pub static $v_point_name: $crate::core::SyncPoint<
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
 			$crate::beh::pl::Mutex<$t>
 		> = $crate::core::SyncPoint::new($crate::beh::pl::const_mutex(
 			$t_make
-		));
+		))
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point whose value is created on first lock.
+		#new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
+			$crate::beh::pl::LazyLock<$crate::beh::pl::Mutex<$t>>
+		> = $crate::core::SyncPoint::<
+			$crate::beh::pl::LazyLock<$crate::beh::pl::Mutex<$t>>
+		>::new($crate::beh::pl::LazyLock::new(
+			|| $crate::beh::pl::const_mutex($t_make)
+		))
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point for each key type `$key`,
+		// used where a static variable cannot be declared (generic functions).
+		#new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+	} => {
+		#[allow(non_snake_case)]
+		let $v_point_name: &'static $crate::core::SyncPoint<
+			$crate::beh::pl::Mutex<$t>
+		> = {
+			static __SYNC_SITE_CACHE: $crate::core::registry::SiteCache = $crate::core::registry::SiteCache::new();
+
+			__SYNC_SITE_CACHE.get_or_init::<$key, _>(|| $crate::core::SyncPoint::new(
+				$crate::beh::pl::const_mutex($t_make)
+			).with_init(|| $t_make).with_name(stringify!($v_point_name)))
+			.register()
+		};
+	};
+	{
+		// Defining a new synchronization point with a separate lock for each key of type `$k`.
+		#new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::KeyedSyncPoint<
+			$k,
+			$crate::beh::pl::Mutex<()>
+		> = $crate::core::KeyedSyncPoint::new();
+	};
+	{
+		// Defining a new table of `$n` synchronization points (stripes)
+		// used to synchronize on object instances.
+		#new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::StripedSyncPoint<
+			$crate::beh::pl::Mutex<()>,
+			{ $n }
+		> = $crate::core::StripedSyncPoint::new(
+			[const { $crate::core::SyncPoint::new($crate::beh::pl::const_mutex(())) }; $n]
+		);
+	};
+	{
+		// Defining a new synchronization point shared by readers
+		// and held exclusively by writers.
+		#new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::RwSyncPoint<
+			$crate::beh::pl::RwLock<$t>
+		> = $crate::core::RwSyncPoint::new($crate::beh::pl::const_rwlock(
+			$t_make
+		))
+		.with_name(stringify!($v_point_name));
 	};
 	{
 		// Creates a new lock on an already created sync point (#new_point)
-		#new_lock($lock:ident): $v_point_name:ident
+		#new_lock($lock:ident): $v_point_name:path
 	} => {
 		#[allow(unused_mut)]
 		let mut $lock = $v_point_name.new_lock();
 	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// waiting no longer than `$timeout`, the lock is wrapped in `Option`
+		#try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.try_lock_for($timeout);
+	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// abandoning the waiting if `$token` is cancelled, the lock is wrapped in `Result`
+		#lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.lock_cancellable($token);
+	};
+	{
+		// Creates new locks on several already created sync points (`SyncPointSet`)
+		#new_lock_all($lock:ident): $v_point_names:expr
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $crate::core::SyncPointSet::lock_all($v_point_names);
+	};
+	{
+		// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+		#new_write_lock($lock:ident): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.write();
+	};
+	{
+		// Creates a new shared lock on an already created sync point (#new_rw_point)
+		#new_read_lock($lock:ident): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.read();
+	};
+	{
+		// Releases the lock (#new_lock) and waits for a notification while `$condition` is true,
+		// the lock is held again after that
+		#wait_while($lock:ident, $condition:expr): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.wait_while($lock, $condition);
+	};
+	{
+		// Replaces the value of an already created sync point (#new_point)
+		// with the initial value
+		#reset: $v_point_name:path
+	} => {
+		$crate::core::SyncPoint::reset(&$v_point_name)
+	};
 	{
 		// Deletes a newly created lock (#new_lock)
-		#drop_lock($lock: ident): $v_point_name:ident
+		#drop_lock($lock: ident): $v_point_name:path
 	} => {
-		$crate::core::SyncPoint::unlock(&$v_point_name, $lock);
+		$v_point_name.unlock($lock);
 	};
 }
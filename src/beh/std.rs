@@ -3,9 +3,83 @@
 
 extern crate std;
 
+use crate::core::RwSyncPointBeh;
 use crate::core::SyncPointBeh;
+use crate::core::SyncPointCapabilities;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use std::sync::Condvar;
+pub use std::sync::LazyLock;
 pub use std::sync::Mutex;
 pub use std::sync::MutexGuard;
+pub use std::sync::RwLock;
+pub use std::sync::RwLockReadGuard;
+pub use std::sync::RwLockWriteGuard;
+use std::sync::TryLockError;
+use std::time::Instant;
+
+/// Number of wait queues shared by all `std` sync points waiting with a time limit.
+const WAIT_QUEUES_LEN: usize = 16;
+
+/// The maximum time between checks of the lock while waiting with a time limit,
+/// in case the release of the lock was not noticed by `unlock`.
+const WAIT_SLICE: Duration = Duration::from_millis(10);
+
+/// The queue of threads waiting with a time limit for the release of `Mutex`.
+///
+/// `std::sync::Mutex` does not support locking with a time limit,
+/// so waiting threads sleep on `Condvar` and are woken up by `unlock`.
+/// `unlock` only reads the number of waiters without synchronizing with them
+/// (nothing is paid while nobody waits), so a release at the moment a thread
+/// starts waiting, or of a guard dropped without `unlock`, can be missed.
+/// Such a release is noticed by the next check of the lock, `WAIT_SLICE` later at most.
+struct WaitQueue {
+	/// Number of threads currently waiting in the queue.
+	waiters: AtomicUsize,
+	lock: Mutex<()>,
+	condvar: Condvar,
+}
+
+impl WaitQueue {
+	#[inline]
+	const fn new() -> Self {
+		Self {
+			waiters: AtomicUsize::new(0),
+			lock: Mutex::new(()),
+			condvar: Condvar::new(),
+		}
+	}
+
+	/// The queue used by `mutex`.
+	#[inline]
+	fn get<T>(mutex: &Mutex<T>) -> &'static Self {
+		static WAIT_QUEUES: [WaitQueue; WAIT_QUEUES_LEN] =
+			[const { WaitQueue::new() }; WAIT_QUEUES_LEN];
+
+		let addr = mutex as *const Mutex<T> as usize;
+		&WAIT_QUEUES[(addr >> 4) % WAIT_QUEUES_LEN]
+	}
+
+	/// Wake up the threads waiting in the queue (if any).
+	#[inline]
+	fn notify(&self) {
+		if self.waiters.load(Ordering::Relaxed) != 0 {
+			let _lock = self.lock.lock();
+			self.condvar.notify_all();
+		}
+	}
+}
+
+/// Lock without waiting, ignoring poisoning (same as `new_lock`).
+#[inline]
+fn try_lock_ignore_poison<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+	match Mutex::try_lock(mutex) {
+		Ok(a) => Some(a),
+		Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+		Err(TryLockError::WouldBlock) => None,
+	}
+}
 
 impl<T> SyncPointBeh for Mutex<T> {
 	type LockType<'a>
@@ -14,6 +88,13 @@ impl<T> SyncPointBeh for Mutex<T> {
 		T: 'a;
 	type DerefLockType = T;
 
+	const CAPABILITIES: SyncPointCapabilities = SyncPointCapabilities {
+		timed_lock: true,
+		try_lock: true,
+		poisoning: true,
+		..SyncPointCapabilities::NONE
+	};
+
 	#[inline]
 	fn new_lock(&self) -> Self::LockType<'_> {
 		match Mutex::lock(self) {
@@ -22,14 +103,148 @@ impl<T> SyncPointBeh for Mutex<T> {
 		}
 	}
 
+	#[inline]
+	fn try_lock_for(&self, timeout: Duration) -> Option<Self::LockType<'_>> {
+		match Instant::now().checked_add(timeout) {
+			Some(deadline) => SyncPointBeh::try_lock_until(self, deadline),
+			None => Some(SyncPointBeh::new_lock(self)),
+		}
+	}
+
+	fn try_lock_until(&self, deadline: Instant) -> Option<Self::LockType<'_>> {
+		if let Some(a) = try_lock_ignore_poison(self) {
+			return Some(a);
+		}
+
+		let queue = WaitQueue::get(self);
+		queue.waiters.fetch_add(1, Ordering::Relaxed);
+		let result = loop {
+			let lock = match queue.lock.lock() {
+				Ok(a) => a,
+				Err(e) => e.into_inner(),
+			};
+			// Checking after registering in the queue so as not to miss the release.
+			if let Some(a) = try_lock_ignore_poison(self) {
+				break Some(a);
+			}
+
+			let now = Instant::now();
+			if now >= deadline {
+				break None;
+			}
+			let _e = queue
+				.condvar
+				.wait_timeout(lock, (deadline - now).min(WAIT_SLICE));
+		};
+		queue.waiters.fetch_sub(1, Ordering::Relaxed);
+
+		result
+	}
+
+	#[inline]
+	fn try_lock(&self) -> Option<Self::LockType<'_>> {
+		try_lock_ignore_poison(self)
+	}
+
+	#[inline]
+	fn unlock<'a>(&'a self, lock_type: Self::LockType<'a>) {
+		drop(lock_type);
+		WaitQueue::get(self).notify();
+	}
+}
+
+/// Lazy initialization of the synchronized value on first lock,
+/// allows to use non-const expressions as the initial value.
+impl<T, F> SyncPointBeh for LazyLock<Mutex<T>, F>
+where
+	F: FnOnce() -> Mutex<T>,
+{
+	type LockType<'a>
+		= MutexGuard<'a, T>
+	where
+		Self: 'a;
+	type DerefLockType = T;
+
+	const CAPABILITIES: SyncPointCapabilities = <Mutex<T> as SyncPointBeh>::CAPABILITIES;
+
+	#[inline]
+	fn new_lock(&self) -> Self::LockType<'_> {
+		SyncPointBeh::new_lock(LazyLock::force(self))
+	}
+
+	#[inline]
+	fn try_lock_for(&self, timeout: Duration) -> Option<Self::LockType<'_>> {
+		SyncPointBeh::try_lock_for(LazyLock::force(self), timeout)
+	}
+
+	#[inline]
+	fn try_lock_until(&self, deadline: Instant) -> Option<Self::LockType<'_>> {
+		SyncPointBeh::try_lock_until(LazyLock::force(self), deadline)
+	}
+
 	#[inline]
 	fn try_lock(&self) -> Option<Self::LockType<'_>> {
-		Mutex::try_lock(self).ok()
+		SyncPointBeh::try_lock(LazyLock::force(self))
 	}
 
 	#[inline]
 	fn unlock<'a>(&'a self, lock_type: Self::LockType<'a>) {
-		drop(lock_type)
+		SyncPointBeh::unlock(LazyLock::force(self), lock_type)
+	}
+}
+
+/// Poisoning is ignored the same way as for `Mutex`.
+impl<T> RwSyncPointBeh for RwLock<T> {
+	type WriteLockType<'a>
+		= RwLockWriteGuard<'a, T>
+	where
+		T: 'a;
+	type ReadLockType<'a>
+		= RwLockReadGuard<'a, T>
+	where
+		T: 'a;
+	type DerefLockType = T;
+
+	#[inline]
+	fn write(&self) -> Self::WriteLockType<'_> {
+		match RwLock::write(self) {
+			Ok(a) => a,
+			Err(e) => e.into_inner(),
+		}
+	}
+
+	#[inline]
+	fn read(&self) -> Self::ReadLockType<'_> {
+		match RwLock::read(self) {
+			Ok(a) => a,
+			Err(e) => e.into_inner(),
+		}
+	}
+
+	#[inline]
+	fn try_write(&self) -> Option<Self::WriteLockType<'_>> {
+		match RwLock::try_write(self) {
+			Ok(a) => Some(a),
+			Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+			Err(TryLockError::WouldBlock) => None,
+		}
+	}
+
+	#[inline]
+	fn try_read(&self) -> Option<Self::ReadLockType<'_>> {
+		match RwLock::try_read(self) {
+			Ok(a) => Some(a),
+			Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+			Err(TryLockError::WouldBlock) => None,
+		}
+	}
+
+	#[inline]
+	fn downgrade<'a>(lock: Self::WriteLockType<'a>) -> Self::ReadLockType<'a>
+	where
+		Self: 'a,
+	{
+		RwLockWriteGuard::downgrade(lock)
 	}
 }
 
@@ -37,14 +252,40 @@ impl<T> SyncPointBeh for Mutex<T> {
 ///
 /// Required for implementation:
 ///
-/// 1. #new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+/// 1. #new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 /// Defining a new synchronization point, usually implements static variables used during synchronization.
-/// 2. #new_lock($lock:ident): $v_point_name:ident
+/// 2. #new_lock($lock:ident): $v_point_name:path
 /// Creates a new lock on an already created sync point (#new_point)
-/// 3. #drop_lock($lock: ident): $v_point_name:ident
+/// 3. #drop_lock($lock: ident): $v_point_name:path
 /// Deletes a newly created lock (#new_lock)
 /// 4. #name
 /// Definition of the current implementation
+/// 5. #capabilities
+/// Capabilities of the synchronization structure of the current implementation
+/// 6. #new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+/// Defining a new synchronization point whose value is created on first lock
+/// 7. #new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+/// Defining a new synchronization point for each key type, stored in `core::registry`
+/// 8. #try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) waiting no longer than `$timeout`, wrapped in `Option`
+/// 9. #lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+/// Creates a new lock (#new_lock) until `$token` is cancelled, wrapped in `Result`
+/// 10. #new_lock_all($lock:ident): $v_point_names:expr
+/// Creates new locks on several sync points at once (`SyncPointSet`)
+/// 11. #new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point with a separate lock for each key (`KeyedSyncPoint`)
+/// 12. #new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new table of `$n` sync points to synchronize on object instances (`StripedSyncPoint`)
+/// 13. #wait_while($lock:ident, $condition:expr): $v_point_name:path
+/// Releases the lock and waits for a notification while `$condition` is true (`SyncPoint::wait_while`)
+/// 14. #reset: $v_point_name:path
+/// Replaces the value of an already created sync point (#new_point) with the initial value (`SyncPoint::reset`)
+/// 15. #new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+/// Defining a new synchronization point shared by readers and held exclusively by writers (`RwSyncPoint`)
+/// 16. #new_write_lock($lock:ident): $v_point_name:path
+/// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+/// 17. #new_read_lock($lock:ident): $v_point_name:path
+/// Creates a new shared lock on an already created sync point (#new_rw_point)
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __sync_beh {
@@ -53,34 +294,187 @@ macro_rules! __sync_beh {
 		#name
 	} => { "std" };
 
+	{
+		// Capabilities of the synchronization structure of the current implementation
+		#capabilities
+	} => { $crate::core::SyncPoint::<$crate::beh::std::Mutex<()>>::CAPABILITIES };
+
 	{
 		// Defining a new synchronization point, usually implements static
 		// variables used during synchronization.
-		#new_point<$t: ty : [$t_make:expr]>: $v_point_name:ident
+		#new_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
 	} => {
 		/// Generated Synchronization Point
 		#[allow(dead_code)]
 		#[allow(non_upper_case_globals)]
 		#[allow(non_camel_case_types)]
-		pub static $v_point_name: $crate::core::SyncPoint<
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
 			$crate::beh::std::Mutex<$t>
 		> = $crate::core::SyncPoint::new(
 			$crate::beh::std::Mutex::new(
 				$t_make
 			)
+		)
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point whose value is created on first lock.
+		#new_lazy_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident $(.$config:ident ( $($config_arg:tt)* ))*
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::SyncPoint<
+			$crate::beh::std::LazyLock<$crate::beh::std::Mutex<$t>>
+		> = $crate::core::SyncPoint::<
+			$crate::beh::std::LazyLock<$crate::beh::std::Mutex<$t>>
+		>::new(
+			$crate::beh::std::LazyLock::new(|| $crate::beh::std::Mutex::new(
+				$t_make
+			))
+		)
+		.with_init(|| $t_make)
+		.with_name(stringify!($v_point_name))
+		.with_registration(|| {
+			$crate::core::SyncPoint::register(&$v_point_name);
+		})
+		$(.$config($($config_arg)*))*;
+	};
+	{
+		// Defining a new synchronization point for each key type `$key`,
+		// used where a static variable cannot be declared (generic functions).
+		#new_typed_point<$t: ty : [$t_make:expr]>: $key:ty => $v_point_name:ident
+	} => {
+		#[allow(non_snake_case)]
+		let $v_point_name: &'static $crate::core::SyncPoint<
+			$crate::beh::std::Mutex<$t>
+		> = {
+			static __SYNC_SITE_CACHE: $crate::core::registry::SiteCache = $crate::core::registry::SiteCache::new();
+
+			__SYNC_SITE_CACHE.get_or_init::<$key, _>(|| $crate::core::SyncPoint::new(
+				$crate::beh::std::Mutex::new($t_make)
+			).with_init(|| $t_make).with_name(stringify!($v_point_name)))
+			.register()
+		};
+	};
+	{
+		// Defining a new synchronization point with a separate lock for each key of type `$k`.
+		#new_keyed_point<$k: ty>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::KeyedSyncPoint<
+			$k,
+			$crate::beh::std::Mutex<()>
+		> = $crate::core::KeyedSyncPoint::new();
+	};
+	{
+		// Defining a new table of `$n` synchronization points (stripes)
+		// used to synchronize on object instances.
+		#new_striped_point<[$n:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::StripedSyncPoint<
+			$crate::beh::std::Mutex<()>,
+			{ $n }
+		> = $crate::core::StripedSyncPoint::new(
+			[const { $crate::core::SyncPoint::new($crate::beh::std::Mutex::new(())) }; $n]
 		);
 	};
+	{
+		// Defining a new synchronization point shared by readers
+		// and held exclusively by writers.
+		#new_rw_point<$t: ty : [$t_make:expr]>: $(#[$meta:meta])* $vis:vis $v_point_name:ident
+	} => {
+		/// Generated Synchronization Point
+		#[allow(dead_code)]
+		#[allow(non_upper_case_globals)]
+		#[allow(non_camel_case_types)]
+		$(#[$meta])*
+		$vis static $v_point_name: $crate::core::RwSyncPoint<
+			$crate::beh::std::RwLock<$t>
+		> = $crate::core::RwSyncPoint::new(
+			$crate::beh::std::RwLock::new(
+				$t_make
+			)
+		)
+		.with_name(stringify!($v_point_name));
+	};
 	{
 		// Creates a new lock on an already created sync point (#new_point)
-		#new_lock($lock:ident): $v_point_name:ident
+		#new_lock($lock:ident): $v_point_name:path
 	} => {
 		#[allow(unused_mut)]
 		let mut $lock = $v_point_name.new_lock();
 	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// waiting no longer than `$timeout`, the lock is wrapped in `Option`
+		#try_lock_for($lock:ident, $timeout:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.try_lock_for($timeout);
+	};
+	{
+		// Creates a new lock on an already created sync point (#new_point),
+		// abandoning the waiting if `$token` is cancelled, the lock is wrapped in `Result`
+		#lock_cancellable($lock:ident, $token:expr): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.lock_cancellable($token);
+	};
+	{
+		// Creates new locks on several already created sync points (`SyncPointSet`)
+		#new_lock_all($lock:ident): $v_point_names:expr
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $crate::core::SyncPointSet::lock_all($v_point_names);
+	};
+	{
+		// Creates a new exclusive lock on an already created sync point (#new_rw_point)
+		#new_write_lock($lock:ident): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.write();
+	};
+	{
+		// Creates a new shared lock on an already created sync point (#new_rw_point)
+		#new_read_lock($lock:ident): $v_point_name:path
+	} => {
+		let $lock = $v_point_name.read();
+	};
+	{
+		// Releases the lock (#new_lock) and waits for a notification while `$condition` is true,
+		// the lock is held again after that
+		#wait_while($lock:ident, $condition:expr): $v_point_name:path
+	} => {
+		#[allow(unused_mut)]
+		let mut $lock = $v_point_name.wait_while($lock, $condition);
+	};
+	{
+		// Replaces the value of an already created sync point (#new_point)
+		// with the initial value
+		#reset: $v_point_name:path
+	} => {
+		$crate::core::SyncPoint::reset(&$v_point_name)
+	};
 	{
 		// Deletes a newly created lock (#new_lock)
-		#drop_lock($lock: ident): $v_point_name:ident
+		#drop_lock($lock: ident): $v_point_name:path
 	} => {
-		$crate::core::SyncPoint::unlock(&$v_point_name, $lock);
+		$v_point_name.unlock($lock);
 	};
 }
@@ -0,0 +1,75 @@
+//! A token for cancelling the waiting for sync points.
+
+extern crate alloc;
+
+use crate::cfg::cfg_async;
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+
+/// A lightweight token that allows one thread (or task) to cancel
+/// the waiting for sync points in others, for example, during shutdown.
+///
+/// Clones of a token share the same state.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+	state: Arc<CancelState>,
+}
+
+/// Shared state of all clones of the token.
+#[derive(Default)]
+struct CancelState {
+	cancelled: AtomicBool,
+
+	/// Wakes up tasks waiting for cancellation.
+	#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+	notify: tokio::sync::Notify,
+}
+
+impl CancelToken {
+	/// Creation of a token that has not been cancelled yet.
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Cancel all current and future waits that use this token.
+	pub fn cancel(&self) {
+		self.state.cancelled.store(true, Ordering::Release);
+
+		#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+		self.state.notify.notify_waiters();
+	}
+
+	/// Whether the token has been cancelled.
+	#[inline]
+	pub fn is_cancelled(&self) -> bool {
+		self.state.cancelled.load(Ordering::Acquire)
+	}
+
+	cfg_async! {
+		/// Wait for the token to be cancelled.
+		pub async fn cancelled(&self) {
+			loop {
+				let notified = self.state.notify.notified();
+				let mut notified = core::pin::pin!(notified);
+				// Registering before checking so as not to miss the cancellation.
+				notified.as_mut().enable();
+
+				if self.is_cancelled() {
+					return;
+				}
+				notified.await;
+			}
+		}
+	}
+}
+
+impl fmt::Debug for CancelToken {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("CancelToken")
+			.field("cancelled", &self.is_cancelled())
+			.finish()
+	}
+}
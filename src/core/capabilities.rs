@@ -0,0 +1,43 @@
+//! Description of the capabilities of the synchronization primitive used by the sync point.
+
+/// A set of flags describing what the synchronization primitive
+/// behind a sync point is able to do.
+///
+/// Allows library code to adapt to the backend selected through `features`,
+/// for example, to skip timeouts if they are not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyncPointCapabilities {
+	/// Locking with a time limit is supported.
+	pub timed_lock: bool,
+	/// An attempt to lock without waiting (`try_lock`) is supported.
+	pub try_lock: bool,
+	/// Checking whether the lock is currently held (`is_lock`) is supported.
+	pub is_lock: bool,
+	/// Waiting threads (or tasks) acquire the lock in the order of the queue.
+	pub fair: bool,
+	/// A panic while holding the lock marks the lock as poisoned.
+	///
+	/// Always set for `SyncPoint`, which tracks poisoning itself (`PoisonPolicy`)
+	/// whatever the synchronization structure does.
+	pub poisoning: bool,
+	/// The lock can be re-acquired by the thread that already holds it.
+	pub reentrant: bool,
+	/// Locking is performed asynchronously (requires `.await`).
+	pub is_async: bool,
+	/// The lock can synchronize code between different processes.
+	pub cross_process: bool,
+}
+
+impl SyncPointCapabilities {
+	/// Capabilities of a primitive that supports nothing.
+	pub const NONE: Self = Self {
+		timed_lock: false,
+		try_lock: false,
+		is_lock: false,
+		fair: false,
+		poisoning: false,
+		reentrant: false,
+		is_async: false,
+		cross_process: false,
+	};
+}
@@ -0,0 +1,97 @@
+//! Waiting for a condition on the data of a sync point.
+
+use crate::cfg::cfg_async;
+use crate::cfg::cfg_not_async;
+
+cfg_not_async! {
+	extern crate std;
+
+	use std::sync::Condvar;
+	use std::sync::Mutex;
+	use std::sync::MutexGuard;
+
+	/// Threads waiting for a condition on the data of the sync point.
+	///
+	/// Each notification changes the generation, a waiting thread captures
+	/// the generation before releasing the sync point, so notifications
+	/// sent after the release are never lost.
+	pub(crate) struct PointCond {
+		generation: Mutex<u64>,
+		condvar: Condvar,
+	}
+
+	impl PointCond {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self {
+				generation: Mutex::new(0),
+				condvar: Condvar::new(),
+			}
+		}
+
+		/// Capture the current generation, must be called before releasing the sync point.
+		#[inline]
+		pub(crate) fn generation(&self) -> MutexGuard<'_, u64> {
+			match self.generation.lock() {
+				Ok(a) => a,
+				Err(e) => e.into_inner(),
+			}
+		}
+
+		/// Wait for the notification following the captured `generation`.
+		pub(crate) fn wait(&self, generation: MutexGuard<'_, u64>) {
+			let current = *generation;
+			let generation = self.condvar.wait_while(generation, |a| *a == current);
+			drop(generation);
+		}
+
+		/// Wake up one waiting thread.
+		pub(crate) fn notify_one(&self) {
+			*self.generation() += 1;
+			self.condvar.notify_one();
+		}
+
+		/// Wake up all waiting threads.
+		pub(crate) fn notify_all(&self) {
+			*self.generation() += 1;
+			self.condvar.notify_all();
+		}
+	}
+}
+
+cfg_async! {
+	use tokio::sync::Notify;
+
+	/// Tasks waiting for a condition on the data of the sync point.
+	pub(crate) struct PointCond {
+		notify: Notify,
+	}
+
+	impl PointCond {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self {
+				notify: Notify::const_new(),
+			}
+		}
+
+		/// A future of the next notification, it must be enabled
+		/// before releasing the sync point.
+		#[inline]
+		pub(crate) fn notified(&self) -> tokio::sync::futures::Notified<'_> {
+			self.notify.notified()
+		}
+
+		/// Wake up one waiting task.
+		#[inline]
+		pub(crate) fn notify_one(&self) {
+			self.notify.notify_one();
+		}
+
+		/// Wake up all waiting tasks.
+		#[inline]
+		pub(crate) fn notify_all(&self) {
+			self.notify.notify_waiters();
+		}
+	}
+}
@@ -0,0 +1,181 @@
+//! Detection of a thread (or task) locking a sync point it already holds,
+//! which would otherwise wait forever.
+//!
+//! The checks are performed in debug builds or with the `debug-checks` feature,
+//! otherwise `PointHolder` is empty.
+
+extern crate std;
+
+use core::fmt;
+use core::panic::Location;
+
+/// The place in the code where the sync point is locked.
+#[derive(Clone, Copy)]
+pub(crate) struct LockSite {
+	location: &'static Location<'static>,
+}
+
+impl LockSite {
+	/// The place of the code calling the current function (`#[track_caller]`),
+	/// for `sync!` it is the place of the macro.
+	#[track_caller]
+	#[inline]
+	pub(crate) fn caller() -> Self {
+		Self {
+			location: Location::caller(),
+		}
+	}
+
+	/// The place of the code.
+	#[inline]
+	pub(crate) const fn location(&self) -> &'static Location<'static> {
+		self.location
+	}
+}
+
+impl fmt::Display for LockSite {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.location.file(), self.location.line())
+	}
+}
+
+#[cfg(any(debug_assertions, feature = "debug-checks"))]
+mod checks {
+	extern crate std;
+
+	use super::LockSite;
+	use std::string::String;
+	use std::sync::Mutex;
+
+	/// Thread (or task) holding the sync point.
+	#[derive(Clone, Copy, PartialEq, Eq)]
+	enum Owner {
+		#[cfg(not(all(feature = "async", not(feature = "pl"), not(feature = "std"))))]
+		Thread(std::thread::ThreadId),
+		#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+		Task(tokio::task::Id),
+	}
+
+	impl Owner {
+		/// The current thread.
+		#[cfg(not(all(feature = "async", not(feature = "pl"), not(feature = "std"))))]
+		#[inline]
+		fn current() -> Option<Self> {
+			Some(Self::Thread(std::thread::current().id()))
+		}
+
+		/// The current task, asynchronous code running outside of a task has no owner.
+		#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+		#[inline]
+		fn current() -> Option<Self> {
+			tokio::task::try_id().map(Self::Task)
+		}
+
+		/// Name of the current owner for messages.
+		fn describe(&self) -> String {
+			match self {
+				#[cfg(not(all(feature = "async", not(feature = "pl"), not(feature = "std"))))]
+				Self::Thread(id) => match std::thread::current().name() {
+					Some(name) => std::format!("thread '{}'", name),
+					None => std::format!("thread {:?}", id),
+				},
+				#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+				Self::Task(id) => std::format!("task {}", id),
+			}
+		}
+	}
+
+	/// The thread (or task) holding the sync point and the place where it was locked.
+	pub(crate) struct PointHolder {
+		holder: Mutex<Option<(Owner, LockSite)>>,
+	}
+
+	impl PointHolder {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self {
+				holder: Mutex::new(None),
+			}
+		}
+
+		/// Current holder of the sync point.
+		#[inline]
+		fn get(&self) -> Option<(Owner, LockSite)> {
+			match self.holder.lock() {
+				Ok(a) => *a,
+				Err(e) => *e.into_inner(),
+			}
+		}
+
+		/// Replace the holder of the sync point.
+		#[inline]
+		fn set(&self, holder: Option<(Owner, LockSite)>) {
+			match self.holder.lock() {
+				Ok(mut a) => *a = holder,
+				Err(e) => *e.into_inner() = holder,
+			}
+		}
+
+		/// Check before waiting for the sync point `name` at `site`
+		/// that the current thread (or task) does not hold it already.
+		///
+		/// # Panics
+		///
+		/// If the current thread (or task) holds the sync point.
+		pub(crate) fn enter(&self, name: &str, site: LockSite) {
+			let Some(owner) = Owner::current() else {
+				return;
+			};
+
+			if let Some((holder, held_site)) = self.get()
+				&& holder == owner
+			{
+				panic!(
+					"{} re-entered {} at {} while holding it from {}",
+					owner.describe(),
+					name,
+					site,
+					held_site,
+				);
+			}
+		}
+
+		/// The sync point was locked at `site` by the current thread (or task).
+		#[inline]
+		pub(crate) fn acquired(&self, site: LockSite) {
+			self.set(Owner::current().map(|owner| (owner, site)));
+		}
+
+		/// The sync point was released.
+		#[inline]
+		pub(crate) fn released(&self) {
+			self.set(None);
+		}
+	}
+}
+
+#[cfg(not(any(debug_assertions, feature = "debug-checks")))]
+mod checks {
+	use super::LockSite;
+
+	/// The checks are disabled, nothing is stored.
+	pub(crate) struct PointHolder;
+
+	impl PointHolder {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self
+		}
+
+		#[inline]
+		pub(crate) fn enter(&self, _name: &str, _site: LockSite) {}
+
+		#[inline]
+		pub(crate) fn acquired(&self, _site: LockSite) {}
+
+		#[inline]
+		pub(crate) fn released(&self) {}
+	}
+}
+
+pub(crate) use checks::PointHolder;
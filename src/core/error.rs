@@ -0,0 +1,41 @@
+//! Errors returned when the sync point could not be locked.
+
+use core::error::Error;
+use core::fmt;
+
+/// The sync point was not released within the specified time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("timed out waiting for the sync point to be released")
+	}
+}
+
+impl Error for TimedOut {}
+
+/// Waiting for the sync point was cancelled by `CancelToken`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("waiting for the sync point was cancelled")
+	}
+}
+
+impl Error for Cancelled {}
+
+/// The sync point is poisoned: the code holding it panicked
+/// (with the `PoisonPolicy::Propagate` policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Poisoned;
+
+impl fmt::Display for Poisoned {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("the sync point is poisoned: the code holding it panicked")
+	}
+}
+
+impl Error for Poisoned {}
@@ -0,0 +1,165 @@
+//! The held lock of a sync point.
+
+extern crate std;
+
+use crate::core::HoldOverrun;
+use crate::core::SyncPoint;
+use crate::core::SyncPointBeh;
+use crate::core::debug::LockSite;
+use crate::core::hold;
+#[cfg(feature = "metrics")]
+use crate::core::metrics::Stopwatch;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ops::DerefMut;
+use core::time::Duration;
+use std::time::Instant;
+
+/// The held lock of the sync point, gives access to its value
+/// and releases the sync point on `unlock` or drop.
+///
+/// Unlike a `sync!` block, the guard can be passed between functions
+/// and released at any time.
+///
+/// ```rust
+///	use synchronized::core::SyncGuard;
+///	use synchronized::core::SyncPointBeh;
+///	use synchronized::sync_point;
+///
+///	sync_point!(COMB_SYNC: (String, usize) = (String::new(), 0));
+///
+///	// Any synchronization structure of the sync point (`std`, `parking_lot`).
+///	fn push<T>(guard: &mut SyncGuard<'_, T>, value: &str)
+///	where
+///		T: SyncPointBeh<DerefLockType = (String, usize)>,
+///	{
+///		guard.0.push_str(value);
+///		guard.1 += 1;
+///	}
+///
+///	let mut guard = COMB_SYNC.new_lock();
+///	push(&mut guard, "a");
+///	push(&mut guard, "b");
+///	assert_eq!(*guard, (String::from("ab"), 2));
+///	guard.unlock();
+///
+///	assert!(COMB_SYNC.try_lock().is_some());
+/// ```
+pub struct SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+{
+	point: &'a SyncPoint<T>,
+	lock: ManuallyDrop<T::LockType<'a>>,
+	/// The sync point is poisoned if the thread panics while holding it
+	/// (the thread was not already panicking when the lock was taken).
+	poison_on_panic: bool,
+	/// When and where the sync point with a budget (`SyncPoint::max_hold`) was locked.
+	hold: Option<(Instant, LockSite, Duration)>,
+	/// When and where the sync point was locked (the `metrics` feature).
+	#[cfg(feature = "metrics")]
+	locked: (Stopwatch, LockSite),
+}
+
+impl<'a, T> SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+{
+	/// Wrapping the lock `lock` held on `point`, taken at `site`.
+	#[inline]
+	pub(crate) fn new(point: &'a SyncPoint<T>, lock: T::LockType<'a>, site: LockSite) -> Self {
+		point.locked_raw(site);
+
+		Self {
+			point,
+			lock: ManuallyDrop::new(lock),
+			poison_on_panic: !std::thread::panicking(),
+			hold: point.max_hold().map(|a| (Instant::now(), site, a)),
+			#[cfg(feature = "metrics")]
+			locked: (Stopwatch::start(), site),
+		}
+	}
+
+	/// The sync point this lock is held on.
+	#[inline]
+	pub const fn point(&self) -> &'a SyncPoint<T> {
+		self.point
+	}
+
+	/// Release the sync point (the same as drop).
+	#[inline]
+	pub fn unlock(self) {
+		drop(self)
+	}
+
+	/// The value was restored after a panic (`SyncTransaction`),
+	/// the sync point is not poisoned on release.
+	#[inline]
+	pub(crate) fn restored(&mut self) {
+		self.poison_on_panic = false;
+	}
+}
+
+impl<'a, T> Deref for SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+{
+	type Target = T::DerefLockType;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.lock
+	}
+}
+
+impl<'a, T> DerefMut for SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+{
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.lock
+	}
+}
+
+impl<'a, T> fmt::Debug for SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+	T::DerefLockType: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("SyncGuard").field(&**self).finish()
+	}
+}
+
+impl<'a, T> Drop for SyncGuard<'a, T>
+where
+	T: SyncPointBeh + 'a,
+{
+	fn drop(&mut self) {
+		if self.poison_on_panic && std::thread::panicking() {
+			self.point.poison();
+		}
+
+		// Safety: the lock is taken only here, the guard is not used after that.
+		let lock = unsafe { ManuallyDrop::take(&mut self.lock) };
+		self.point.unlock_raw(lock);
+		#[cfg(feature = "metrics")]
+		self.point
+			.metrics
+			.released(self.locked.1, self.locked.0.elapsed());
+
+		if let Some((since, site, max_hold)) = self.hold {
+			let held = since.elapsed();
+			if held > max_hold && !std::thread::panicking() {
+				hold::overrun(HoldOverrun {
+					name: self.point.name(),
+					location: site.location(),
+					held,
+					max_hold,
+				});
+			}
+		}
+	}
+}
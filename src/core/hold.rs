@@ -0,0 +1,152 @@
+//! Budget of the time a sync point may be held, the blocks exceeding it
+//! are reported when the sync point is released.
+//!
+//! Overruns are detected only on release: a block that never releases
+//! the sync point (a deadlock, an endless loop) is never reported.
+
+extern crate alloc;
+extern crate std;
+
+use alloc::sync::Arc;
+use core::fmt;
+use core::panic::Location;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use std::sync::RwLock;
+
+/// The sync point was held longer than its budget (`SyncPoint::with_max_hold`).
+#[derive(Debug, Clone, Copy)]
+pub struct HoldOverrun {
+	/// Name of the sync point.
+	pub name: &'static str,
+	/// The place of the code (`sync!`) where the sync point was locked.
+	pub location: &'static Location<'static>,
+	/// How long the sync point was held.
+	pub held: Duration,
+	/// The budget of the sync point.
+	pub max_hold: Duration,
+}
+
+impl fmt::Display for HoldOverrun {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} locked at {} was held for {:?}, longer than {:?}",
+			self.name, self.location, self.held, self.max_hold,
+		)
+	}
+}
+
+/// The budget of sync points without their own budget in nanoseconds, 0 if there is none.
+static DEFAULT_MAX_HOLD: AtomicU64 = AtomicU64::new(0);
+
+type OverrunCallback = Arc<dyn Fn(&HoldOverrun) + Send + Sync>;
+
+/// The function called for each overrun, if not set, then overruns are printed to stderr.
+static CALLBACK: RwLock<Option<OverrunCallback>> = RwLock::new(None);
+
+/// Set the budget of all sync points that do not have their own budget
+/// (`None` removes it).
+pub fn set_default_max_hold(max_hold: Option<Duration>) {
+	let nanos = match max_hold {
+		Some(a) => (a.as_nanos() as u64).max(1),
+		None => 0,
+	};
+
+	DEFAULT_MAX_HOLD.store(nanos, Ordering::Relaxed);
+}
+
+/// The budget of sync points that do not have their own budget.
+#[inline]
+pub fn default_max_hold() -> Option<Duration> {
+	match DEFAULT_MAX_HOLD.load(Ordering::Relaxed) {
+		0 => None,
+		a => Some(Duration::from_nanos(a)),
+	}
+}
+
+/// Set the function called (in the thread that held the sync point, after its release)
+/// for each sync point held longer than its budget.
+///
+/// The budget is checked only when the sync point is released, a block
+/// still holding it is not reported however long it has been running.
+///
+/// The function may replace itself with `set_hold_overrun_callback`.
+///
+/// A function that panics makes the `sync!` block exceeding the budget panic,
+/// for example, to fail the test.
+///
+/// ```rust
+///	use std::time::Duration;
+///	use synchronized::core::set_hold_overrun_callback;
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point! {
+///		#[max_hold = "1ms"]
+///		SLOW_SYNC: usize = 0
+///	}
+///
+///	set_hold_overrun_callback(|overrun| panic!("{}", overrun));
+///
+///	let result = std::thread::spawn(|| {
+///		sync!(->SLOW_SYNC(count) {
+///			std::thread::sleep(Duration::from_millis(10));
+///			*count += 1;
+///		});
+///	})
+///	.join();
+///	assert!(result.is_err());
+/// ```
+pub fn set_hold_overrun_callback(callback: impl Fn(&HoldOverrun) + Send + Sync + 'static) {
+	let callback: OverrunCallback = Arc::new(callback);
+	match CALLBACK.write() {
+		Ok(mut a) => *a = Some(callback),
+		Err(e) => *e.into_inner() = Some(callback),
+	}
+}
+
+/// Report the overrun to the callback (`set_hold_overrun_callback`).
+pub(crate) fn overrun(overrun: HoldOverrun) {
+	// Called outside of the lock, the callback may set another one.
+	let callback = match CALLBACK.read() {
+		Ok(a) => a.clone(),
+		Err(e) => e.into_inner().clone(),
+	};
+
+	match callback {
+		Some(callback) => callback(&overrun),
+		None => std::eprintln!("synchronized: {}", overrun),
+	}
+}
+
+/// Parsing of the budget written in `sync_point` (`#[max_hold = "10ms"]`):
+/// an integer followed by `ns`, `us`, `ms`, `s` or `m`.
+///
+/// # Panics
+///
+/// If the budget is written incorrectly (at compile time for `sync_point`).
+#[doc(hidden)]
+pub const fn parse_max_hold(value: &str) -> Duration {
+	let bytes = value.as_bytes();
+	let mut number = 0u64;
+	let mut i = 0;
+	while i < bytes.len() && bytes[i].is_ascii_digit() {
+		number = number * 10 + (bytes[i] - b'0') as u64;
+		i += 1;
+	}
+	if i == 0 {
+		panic!("`max_hold` must start with a number, for example \"10ms\"");
+	}
+
+	let (_, unit) = bytes.split_at(i);
+	match unit {
+		b"ns" => Duration::from_nanos(number),
+		b"us" => Duration::from_micros(number),
+		b"ms" => Duration::from_millis(number),
+		b"s" => Duration::from_secs(number),
+		b"m" => Duration::from_secs(number * 60),
+		_ => panic!("the unit of `max_hold` must be `ns`, `us`, `ms`, `s` or `m`"),
+	}
+}
@@ -0,0 +1,143 @@
+//! Tracking of the threads (or tasks) holding the sync points
+//! (the `track-holders` feature).
+
+extern crate alloc;
+extern crate std;
+
+use crate::core::debug::LockSite;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::panic::Location;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::thread::ThreadId;
+use std::time::Instant;
+
+/// The thread (or task) holding a sync point.
+#[derive(Debug, Clone)]
+pub struct Holder {
+	/// Id of the thread that locked the sync point.
+	pub thread_id: ThreadId,
+	/// Name of the thread that locked the sync point.
+	pub thread_name: Option<String>,
+	/// Id of the task that locked the sync point
+	/// (asynchronous code running outside of a task has no id).
+	#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub task_id: Option<tokio::task::Id>,
+	/// When the sync point was locked.
+	pub since: Instant,
+	/// The place of the code (`sync!`) where the sync point was locked.
+	pub location: &'static Location<'static>,
+}
+
+impl Holder {
+	/// How long the sync point has been held.
+	#[inline]
+	pub fn held_for(&self) -> Duration {
+		self.since.elapsed()
+	}
+}
+
+impl fmt::Display for Holder {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.thread_name {
+			Some(name) => write!(f, "thread '{}'", name)?,
+			None => write!(f, "thread {:?}", self.thread_id)?,
+		}
+		#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+		if let Some(task_id) = self.task_id {
+			write!(f, " (task {})", task_id)?;
+		}
+
+		write!(f, " at {} for {:?}", self.location, self.held_for())
+	}
+}
+
+/// A sync point held right now (`dump_holders`).
+#[derive(Debug, Clone)]
+pub struct HeldPoint {
+	/// Name of the sync point.
+	pub name: &'static str,
+	/// The thread (or task) holding the sync point.
+	pub holder: Holder,
+}
+
+impl fmt::Display for HeldPoint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} is held by {}", self.name, self.holder)
+	}
+}
+
+/// Sync points held right now by their addresses.
+static HELD: Mutex<Option<HashMap<usize, HeldPoint>>> = Mutex::new(None);
+
+fn held() -> MutexGuard<'static, Option<HashMap<usize, HeldPoint>>> {
+	match HELD.lock() {
+		Ok(a) => a,
+		Err(e) => e.into_inner(),
+	}
+}
+
+/// The sync point `point` named `name` was locked at `site` by the current thread (or task).
+pub(crate) fn acquired(point: usize, name: &'static str, site: LockSite) {
+	let thread = std::thread::current();
+	let holder = Holder {
+		thread_id: thread.id(),
+		thread_name: thread.name().map(String::from),
+		#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+		task_id: tokio::task::try_id(),
+		since: Instant::now(),
+		location: site.location(),
+	};
+
+	held()
+		.get_or_insert_with(HashMap::new)
+		.insert(point, HeldPoint { name, holder });
+}
+
+/// The sync point `point` was released.
+pub(crate) fn released(point: usize) {
+	if let Some(held) = held().as_mut() {
+		held.remove(&point);
+	}
+}
+
+/// The holder of the sync point `point`.
+pub(crate) fn holder(point: usize) -> Option<Holder> {
+	held()
+		.as_ref()
+		.and_then(|a| a.get(&point))
+		.map(|a| a.holder.clone())
+}
+
+/// All sync points held right now, starting with the longest held,
+/// for example, to find out who holds a sync point when the program hangs.
+///
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(COMB_SYNC: usize = 0);
+///
+///	sync!(->COMB_SYNC(count) {
+///		let holders = synchronized::dump_holders();
+///		let comb = holders.iter().find(|a| a.name == "COMB_SYNC").unwrap();
+///		assert_eq!(comb.holder.thread_id, std::thread::current().id());
+///
+///		*count += 1;
+///	});
+///	assert!(COMB_SYNC.holder().is_none());
+/// ```
+pub fn dump_holders() -> Vec<HeldPoint> {
+	let mut result: Vec<HeldPoint> = held()
+		.as_ref()
+		.map(|a| a.values().cloned().collect())
+		.unwrap_or_default();
+	result.sort_by_key(|a| a.holder.since);
+
+	result
+}
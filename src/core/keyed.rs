@@ -0,0 +1,174 @@
+//! Sync points with a separate lock for each key value.
+
+extern crate alloc;
+extern crate std;
+
+use crate::core::SyncPoint;
+use crate::core::SyncPointBeh;
+use alloc::sync::Arc;
+use core::hash::BuildHasher;
+use core::hash::BuildHasherDefault;
+use core::hash::Hash;
+use core::ops::Deref;
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+/// Number of shards of the table of locks.
+const SHARDS_LEN: usize = 16;
+
+/// Hasher used to select the shard and inside the shards.
+type KeyHasher = BuildHasherDefault<DefaultHasher>;
+
+/// Part of the table of locks, keys are distributed between shards by hash.
+type Shard<K, T> = Mutex<HashMap<K, Arc<SyncPoint<T>>, KeyHasher>>;
+
+/// Hash of `key`, allows to use keys of any type with `KeyedSyncPoint<u64, T>`
+/// (keys with the same hash share the same lock).
+#[inline]
+pub fn key_hash<Q: Hash + ?Sized>(key: &Q) -> u64 {
+	KeyHasher::new().hash_one(key)
+}
+
+/// Sync point with a separate lock for each key value,
+/// only one thread (or task) at a time is executed for the same key.
+///
+/// Locks are created on first use, reference-counted
+/// and removed from the table when they are no longer held.
+pub struct KeyedSyncPoint<K, T>
+where
+	T: SyncPointBeh,
+{
+	shards: [Shard<K, T>; SHARDS_LEN],
+}
+
+impl<K, T> KeyedSyncPoint<K, T>
+where
+	T: SyncPointBeh,
+{
+	/// Creation of an empty table of locks.
+	#[inline]
+	pub const fn new() -> Self {
+		Self {
+			shards: [const { Mutex::new(HashMap::with_hasher(KeyHasher::new())) }; SHARDS_LEN],
+		}
+	}
+}
+
+impl<K, T> Default for KeyedSyncPoint<K, T>
+where
+	T: SyncPointBeh,
+{
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K, T> KeyedSyncPoint<K, T>
+where
+	K: Hash + Eq,
+	T: SyncPointBeh,
+{
+	/// The shard containing `key`.
+	#[inline]
+	fn shard(&self, key: &K) -> MutexGuard<'_, HashMap<K, Arc<SyncPoint<T>>, KeyHasher>> {
+		let shard = &self.shards[key_hash(key) as usize % SHARDS_LEN];
+		match shard.lock() {
+			Ok(a) => a,
+			Err(e) => e.into_inner(),
+		}
+	}
+
+	/// Number of keys whose locks are currently in use.
+	pub fn len(&self) -> usize {
+		self.shards
+			.iter()
+			.map(|shard| match shard.lock() {
+				Ok(a) => a.len(),
+				Err(e) => e.into_inner().len(),
+			})
+			.sum()
+	}
+
+	/// Whether no key lock is currently in use.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<K, T> KeyedSyncPoint<K, T>
+where
+	K: Hash + Eq + Clone,
+	T: SyncPointBeh + Default,
+{
+	/// Get the sync point of `key` (create it if it does not exist yet),
+	/// the sync point stays in the table at least as long as `KeyedEntry` exists.
+	pub fn entry(&self, key: K) -> KeyedEntry<'_, K, T> {
+		let point = self
+			.shard(&key)
+			.entry(key.clone())
+			.or_insert_with(|| Arc::new(SyncPoint::new(T::default())))
+			.clone();
+
+		KeyedEntry {
+			keyed: self,
+			key,
+			point,
+		}
+	}
+}
+
+/// Sync point of one key of `KeyedSyncPoint`,
+/// it is removed from the table when it is no longer in use.
+pub struct KeyedEntry<'a, K, T>
+where
+	K: Hash + Eq,
+	T: SyncPointBeh,
+{
+	keyed: &'a KeyedSyncPoint<K, T>,
+	key: K,
+	point: Arc<SyncPoint<T>>,
+}
+
+impl<K, T> KeyedEntry<'_, K, T>
+where
+	K: Hash + Eq,
+	T: SyncPointBeh,
+{
+	/// The key of this sync point.
+	#[inline]
+	pub const fn key(&self) -> &K {
+		&self.key
+	}
+}
+
+impl<K, T> Deref for KeyedEntry<'_, K, T>
+where
+	K: Hash + Eq,
+	T: SyncPointBeh,
+{
+	type Target = SyncPoint<T>;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.point
+	}
+}
+
+impl<K, T> Drop for KeyedEntry<'_, K, T>
+where
+	K: Hash + Eq,
+	T: SyncPointBeh,
+{
+	fn drop(&mut self) {
+		let mut shard = self.keyed.shard(&self.key);
+		// New references are only created under the lock of the shard,
+		// so if only the table and this entry refer to the point, it is no longer in use.
+		if Arc::strong_count(&self.point) == 2 {
+			shard.remove(&self.key);
+		}
+	}
+}
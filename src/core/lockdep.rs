@@ -0,0 +1,265 @@
+//! Validation of the order in which named sync points are locked
+//! (the `lockdep` feature).
+//!
+//! Each time a sync point is locked while other sync points are held,
+//! the order "held -> locked" is recorded. If the new order contradicts
+//! an order recorded earlier (the orders form a cycle), two threads (or tasks)
+//! can deadlock each other, even if it did not happen in this run.
+
+#[cfg(feature = "lockdep")]
+mod checks {
+	extern crate alloc;
+	extern crate std;
+
+	use crate::core::debug::LockSite;
+	use alloc::string::String;
+	use alloc::vec::Vec;
+	use std::collections::HashMap;
+	use std::collections::HashSet;
+	use std::sync::Mutex;
+
+	/// Sync point held by the current thread (or task).
+	#[derive(Clone, Copy)]
+	struct Held {
+		point: usize,
+		name: &'static str,
+		site: LockSite,
+	}
+
+	/// The recorded order "`from` is held -> `to` is locked".
+	#[derive(Clone, Copy)]
+	struct Edge {
+		from: Held,
+		to: Held,
+	}
+
+	/// Orders of locking of the sync points recorded so far.
+	struct Graph {
+		edges: HashMap<(usize, usize), Edge>,
+		next: HashMap<usize, Vec<usize>>,
+	}
+
+	impl Graph {
+		/// Chain of recorded orders leading from the point `from` to the point `to`.
+		fn path(&self, from: usize, to: usize) -> Option<Vec<Edge>> {
+			let mut visited = HashSet::new();
+			let mut stack = Vec::from([(from, Vec::new())]);
+			while let Some((point, chain)) = stack.pop() {
+				if point == to {
+					return Some(chain);
+				}
+				if !visited.insert(point) {
+					continue;
+				}
+
+				for next in self.next.get(&point).into_iter().flatten() {
+					let mut chain = chain.clone();
+					chain.push(self.edges[&(point, *next)]);
+					stack.push((*next, chain));
+				}
+			}
+
+			None
+		}
+	}
+
+	static GRAPH: Mutex<Option<Graph>> = Mutex::new(None);
+
+	/// Description of the chain of orders for messages.
+	fn describe(chain: &[Edge]) -> String {
+		let mut result = String::new();
+		for edge in chain {
+			if !result.is_empty() {
+				result.push_str(", ");
+			}
+
+			result.push_str(&std::format!(
+				"{} (locked at {}) -> {} (locked at {})",
+				edge.from.name,
+				edge.from.site,
+				edge.to.name,
+				edge.to.site,
+			));
+		}
+
+		result
+	}
+
+	/// Check the order of locking the sync point `point` named `name` at `site`
+	/// against the sync points held by the current thread (or task)
+	/// and record it.
+	///
+	/// # Panics
+	///
+	/// If the order contradicts an order recorded earlier.
+	pub(crate) fn enter(point: usize, name: &'static str, site: LockSite) {
+		let Some(held) = held::get() else {
+			return;
+		};
+		let to = Held { point, name, site };
+
+		let mut inversion = None;
+		{
+			let mut graph = match GRAPH.lock() {
+				Ok(a) => a,
+				Err(e) => e.into_inner(),
+			};
+			let graph = graph.get_or_insert_with(|| Graph {
+				edges: HashMap::new(),
+				next: HashMap::new(),
+			});
+
+			for from in held.into_iter().filter(|a| a.point != point) {
+				if graph.edges.contains_key(&(from.point, point)) {
+					continue;
+				}
+				if let Some(chain) = graph.path(point, from.point) {
+					inversion = Some((Edge { from, to }, chain));
+					break;
+				}
+
+				graph.edges.insert((from.point, point), Edge { from, to });
+				graph.next.entry(from.point).or_default().push(point);
+			}
+		}
+
+		if let Some((edge, chain)) = inversion {
+			panic!(
+				"lock order inversion of {} and {}: {}, but earlier: {}",
+				edge.from.name,
+				edge.to.name,
+				describe(&[edge]),
+				describe(&chain),
+			);
+		}
+	}
+
+	/// The sync point `point` named `name` was locked at `site` by the current thread (or task).
+	#[inline]
+	pub(crate) fn acquired(point: usize, name: &'static str, site: LockSite) {
+		held::push(Held { point, name, site });
+	}
+
+	/// The sync point `point` was released by the current thread (or task).
+	#[inline]
+	pub(crate) fn released(point: usize) {
+		held::remove(point);
+	}
+
+	#[cfg(not(all(feature = "async", not(feature = "pl"), not(feature = "std"))))]
+	mod held {
+		extern crate alloc;
+		extern crate std;
+
+		use super::Held;
+		use alloc::vec::Vec;
+		use core::cell::RefCell;
+
+		std::thread_local! {
+			/// Sync points held by the current thread in the order of locking.
+			static HELD: RefCell<Vec<Held>> = const { RefCell::new(Vec::new()) };
+		}
+
+		/// Sync points held by the current thread.
+		pub(super) fn get() -> Option<Vec<Held>> {
+			Some(HELD.with_borrow(|a| a.clone()))
+		}
+
+		pub(super) fn push(held: Held) {
+			HELD.with_borrow_mut(|a| a.push(held));
+		}
+
+		pub(super) fn remove(point: usize) {
+			HELD.with_borrow_mut(|a| {
+				if let Some(i) = a.iter().rposition(|a| a.point == point) {
+					a.remove(i);
+				}
+			});
+		}
+	}
+
+	#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+	mod held {
+		extern crate alloc;
+		extern crate std;
+
+		use super::Held;
+		use alloc::vec::Vec;
+		use std::collections::HashMap;
+		use std::sync::Mutex;
+		use std::sync::MutexGuard;
+
+		/// Sync points held by each task in the order of locking,
+		/// a task can move between threads while holding them.
+		static HELD: Mutex<Option<HashMap<tokio::task::Id, Vec<Held>>>> = Mutex::new(None);
+
+		fn lock() -> MutexGuard<'static, Option<HashMap<tokio::task::Id, Vec<Held>>>> {
+			match HELD.lock() {
+				Ok(a) => a,
+				Err(e) => e.into_inner(),
+			}
+		}
+
+		/// Sync points held by the current task,
+		/// asynchronous code running outside of a task is not checked.
+		pub(super) fn get() -> Option<Vec<Held>> {
+			let task = tokio::task::try_id()?;
+
+			Some(
+				lock()
+					.as_ref()
+					.and_then(|a| a.get(&task))
+					.cloned()
+					.unwrap_or_default(),
+			)
+		}
+
+		pub(super) fn push(held: Held) {
+			if let Some(task) = tokio::task::try_id() {
+				lock()
+					.get_or_insert_with(HashMap::new)
+					.entry(task)
+					.or_default()
+					.push(held);
+			}
+		}
+
+		pub(super) fn remove(point: usize) {
+			let mut lock = lock();
+			let Some(tasks) = lock.as_mut() else {
+				return;
+			};
+
+			// The sync point can be released by another task than the one that locked it.
+			let Some((&task, held)) = tasks
+				.iter_mut()
+				.find(|(_, a)| a.iter().any(|a| a.point == point))
+			else {
+				return;
+			};
+
+			held.retain(|a| a.point != point);
+			if held.is_empty() {
+				tasks.remove(&task);
+			}
+		}
+	}
+}
+
+#[cfg(not(feature = "lockdep"))]
+mod checks {
+	use crate::core::debug::LockSite;
+
+	#[inline]
+	pub(crate) fn enter(_point: usize, _name: &'static str, _site: LockSite) {}
+
+	#[inline]
+	pub(crate) fn acquired(_point: usize, _name: &'static str, _site: LockSite) {}
+
+	#[inline]
+	pub(crate) fn released(_point: usize) {}
+}
+
+pub(crate) use checks::acquired;
+pub(crate) use checks::enter;
+pub(crate) use checks::released;
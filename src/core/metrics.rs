@@ -0,0 +1,330 @@
+//! Counters of locking of the sync points (the `metrics` feature):
+//! acquisitions, contention, failures of `try_lock`, time of waiting
+//! and time of holding, together and for each place of the code (`sync!`).
+//!
+//! The counters are atomics, locking a sync point never waits for them.
+//! The first `MAX_STATS_SITES` places of the code locking a sync point are counted
+//! separately, the others only in the counters of all places together.
+//!
+//! Without the feature `PointMetrics` and `Stopwatch` are empty
+//! and nothing is measured.
+
+#[cfg(feature = "metrics")]
+mod counters {
+	extern crate alloc;
+	extern crate std;
+
+	use crate::core::debug::LockSite;
+	use alloc::vec::Vec;
+	use core::panic::Location;
+	use core::ptr;
+	use core::sync::atomic::AtomicPtr;
+	use core::sync::atomic::AtomicU64;
+	use core::sync::atomic::Ordering;
+	use core::time::Duration;
+	use std::time::Instant;
+
+	/// The counters are kept.
+	pub(crate) const ENABLED: bool = true;
+
+	/// Number of places of the code counted separately for each sync point.
+	pub const MAX_STATS_SITES: usize = 16;
+
+	/// Snapshot of the counters of locking of a sync point (or of one place of the code).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+	pub struct LockStats {
+		/// Number of times the sync point was locked.
+		pub acquisitions: u64,
+		/// Number of times the sync point was already locked and had to be waited for.
+		pub contended: u64,
+		/// Number of times `try_lock` (or waiting with a timeout) did not lock the sync point.
+		pub try_lock_failures: u64,
+		/// Total time of waiting for the sync point.
+		pub total_wait: Duration,
+		/// The longest waiting for the sync point.
+		pub max_wait: Duration,
+		/// Total time the sync point was held.
+		pub total_hold: Duration,
+		/// The longest time the sync point was held.
+		pub max_hold: Duration,
+	}
+
+	/// Counters of locking of one place of the code (`sync!`).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct SiteStats {
+		/// The place of the code where the sync point was locked.
+		pub location: &'static Location<'static>,
+		/// Counters of this place.
+		pub stats: LockStats,
+	}
+
+	/// Snapshot of the counters of locking of a sync point (`SyncPoint::stats`).
+	#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+	pub struct SyncPointStats {
+		/// Counters of all places together.
+		pub total: LockStats,
+		/// Counters of each place of the code where the sync point was locked
+		/// (no more than `MAX_STATS_SITES` places).
+		pub sites: Vec<SiteStats>,
+	}
+
+	/// Atomic counters behind `LockStats`.
+	struct Counters {
+		acquisitions: AtomicU64,
+		contended: AtomicU64,
+		try_lock_failures: AtomicU64,
+		total_wait: AtomicU64,
+		max_wait: AtomicU64,
+		total_hold: AtomicU64,
+		max_hold: AtomicU64,
+	}
+
+	impl Counters {
+		const fn new() -> Self {
+			Self {
+				acquisitions: AtomicU64::new(0),
+				contended: AtomicU64::new(0),
+				try_lock_failures: AtomicU64::new(0),
+				total_wait: AtomicU64::new(0),
+				max_wait: AtomicU64::new(0),
+				total_hold: AtomicU64::new(0),
+				max_hold: AtomicU64::new(0),
+			}
+		}
+
+		fn acquired(&self, wait: Option<Duration>) {
+			self.acquisitions.fetch_add(1, Ordering::Relaxed);
+			if let Some(wait) = wait {
+				let wait = wait.as_nanos() as u64;
+				self.contended.fetch_add(1, Ordering::Relaxed);
+				self.total_wait.fetch_add(wait, Ordering::Relaxed);
+				self.max_wait.fetch_max(wait, Ordering::Relaxed);
+			}
+		}
+
+		fn try_lock_failed(&self) {
+			self.try_lock_failures.fetch_add(1, Ordering::Relaxed);
+		}
+
+		fn released(&self, hold: Duration) {
+			let hold = hold.as_nanos() as u64;
+			self.total_hold.fetch_add(hold, Ordering::Relaxed);
+			self.max_hold.fetch_max(hold, Ordering::Relaxed);
+		}
+
+		/// Nothing was counted since the creation (or `reset`).
+		fn is_empty(&self) -> bool {
+			self.acquisitions.load(Ordering::Relaxed) == 0
+				&& self.try_lock_failures.load(Ordering::Relaxed) == 0
+				&& self.total_hold.load(Ordering::Relaxed) == 0
+		}
+
+		fn stats(&self) -> LockStats {
+			LockStats {
+				acquisitions: self.acquisitions.load(Ordering::Relaxed),
+				contended: self.contended.load(Ordering::Relaxed),
+				try_lock_failures: self.try_lock_failures.load(Ordering::Relaxed),
+				total_wait: Duration::from_nanos(self.total_wait.load(Ordering::Relaxed)),
+				max_wait: Duration::from_nanos(self.max_wait.load(Ordering::Relaxed)),
+				total_hold: Duration::from_nanos(self.total_hold.load(Ordering::Relaxed)),
+				max_hold: Duration::from_nanos(self.max_hold.load(Ordering::Relaxed)),
+			}
+		}
+
+		fn reset(&self) {
+			for a in [
+				&self.acquisitions,
+				&self.contended,
+				&self.try_lock_failures,
+				&self.total_wait,
+				&self.max_wait,
+				&self.total_hold,
+				&self.max_hold,
+			] {
+				a.store(0, Ordering::Relaxed);
+			}
+		}
+	}
+
+	/// Measurement of the time of waiting for (or holding) a sync point.
+	#[derive(Clone, Copy)]
+	pub(crate) struct Stopwatch(Instant);
+
+	impl Stopwatch {
+		#[inline]
+		pub(crate) fn start() -> Self {
+			Self(Instant::now())
+		}
+
+		#[inline]
+		pub(crate) fn elapsed(&self) -> Duration {
+			self.0.elapsed()
+		}
+	}
+
+	/// Counters of one place of the code, `location` is null while the slot is free.
+	struct Site {
+		location: AtomicPtr<Location<'static>>,
+		counters: Counters,
+	}
+
+	impl Site {
+		const fn new() -> Self {
+			Self {
+				location: AtomicPtr::new(ptr::null_mut()),
+				counters: Counters::new(),
+			}
+		}
+	}
+
+	/// Counters of locking of a sync point, together and for each place of the code.
+	pub(crate) struct PointMetrics {
+		total: Counters,
+		/// Open addressing table of the places, a taken slot is never freed.
+		sites: [Site; MAX_STATS_SITES],
+	}
+
+	impl PointMetrics {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self {
+				total: Counters::new(),
+				sites: [const { Site::new() }; MAX_STATS_SITES],
+			}
+		}
+
+		/// Counters of the place `site`, a free slot is taken for a new place,
+		/// None if all slots are taken by other places.
+		fn site(&self, site: LockSite) -> Option<&Counters> {
+			let location = ptr::from_ref(site.location()).cast_mut();
+			let start = (location as usize / align_of::<Location>()) % MAX_STATS_SITES;
+
+			for i in 0..MAX_STATS_SITES {
+				let slot = &self.sites[(start + i) % MAX_STATS_SITES];
+				let current = match slot.location.compare_exchange(
+					ptr::null_mut(),
+					location,
+					Ordering::AcqRel,
+					Ordering::Acquire,
+				) {
+					Ok(_) => return Some(&slot.counters),
+					Err(a) => a,
+				};
+				if current == location {
+					return Some(&slot.counters);
+				}
+			}
+
+			None
+		}
+
+		/// Change the counters of the place `site` and of all places together with `f`.
+		#[inline]
+		fn update(&self, site: LockSite, f: impl Fn(&Counters)) {
+			f(&self.total);
+			if let Some(counters) = self.site(site) {
+				f(counters);
+			}
+		}
+
+		/// The sync point was locked at `site`, after waiting for `wait` if it was contended.
+		#[inline]
+		pub(crate) fn acquired(&self, site: LockSite, wait: Option<Duration>) {
+			self.update(site, |a| a.acquired(wait));
+		}
+
+		/// The sync point was not locked at `site` without waiting (or in time).
+		#[inline]
+		pub(crate) fn try_lock_failed(&self, site: LockSite) {
+			self.update(site, Counters::try_lock_failed);
+		}
+
+		/// The sync point locked at `site` was released after being held for `hold`.
+		#[inline]
+		pub(crate) fn released(&self, site: LockSite, hold: Duration) {
+			self.update(site, |a| a.released(hold));
+		}
+
+		/// Snapshot of the counters, the places with nothing counted are skipped.
+		pub(crate) fn stats(&self) -> SyncPointStats {
+			SyncPointStats {
+				total: self.total.stats(),
+				sites: self
+					.sites
+					.iter()
+					.filter(|a| !a.counters.is_empty())
+					.filter_map(|a| {
+						// SAFETY: only `&'static Location` are stored in the slots.
+						let location = unsafe { a.location.load(Ordering::Acquire).as_ref()? };
+
+						Some(SiteStats {
+							location,
+							stats: a.counters.stats(),
+						})
+					})
+					.collect(),
+			}
+		}
+
+		/// Set all counters to zero, the places of the code keep their slots.
+		pub(crate) fn reset(&self) {
+			self.total.reset();
+			for a in &self.sites {
+				a.counters.reset();
+			}
+		}
+	}
+}
+
+#[cfg(not(feature = "metrics"))]
+mod counters {
+	use crate::core::debug::LockSite;
+	use core::time::Duration;
+
+	/// The counters are not kept.
+	pub(crate) const ENABLED: bool = false;
+
+	/// Nothing is measured.
+	#[derive(Clone, Copy)]
+	pub(crate) struct Stopwatch;
+
+	impl Stopwatch {
+		#[inline]
+		pub(crate) fn start() -> Self {
+			Self
+		}
+
+		#[inline]
+		pub(crate) fn elapsed(&self) -> Duration {
+			Duration::ZERO
+		}
+	}
+
+	/// The counters are disabled, nothing is stored.
+	pub(crate) struct PointMetrics;
+
+	impl PointMetrics {
+		#[inline]
+		pub(crate) const fn new() -> Self {
+			Self
+		}
+
+		#[inline]
+		pub(crate) fn acquired(&self, _site: LockSite, _wait: Option<Duration>) {}
+
+		#[inline]
+		pub(crate) fn try_lock_failed(&self, _site: LockSite) {}
+	}
+}
+
+pub(crate) use counters::ENABLED;
+#[cfg(feature = "metrics")]
+pub use counters::LockStats;
+#[cfg(feature = "metrics")]
+pub use counters::MAX_STATS_SITES;
+pub(crate) use counters::PointMetrics;
+#[cfg(feature = "metrics")]
+pub use counters::SiteStats;
+pub(crate) use counters::Stopwatch;
+#[cfg(feature = "metrics")]
+pub use counters::SyncPointStats;
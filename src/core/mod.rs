@@ -1,14 +1,110 @@
 //! The core of the library that defines the basic primitives.
 
+extern crate std;
+
 pub(crate) mod r#async;
+mod cancel;
+mod capabilities;
+mod cond;
+pub(crate) mod debug;
+mod error;
+mod guard;
+mod hold;
+#[cfg(feature = "track-holders")]
+mod holders;
+mod keyed;
+mod lockdep;
+mod metrics;
+mod multi;
+mod poison;
+mod reentrant;
+pub mod registry;
+mod reset;
+mod rw;
+mod striped;
+mod transaction;
 
 use r#async::cfg_async_or_sync;
+pub use cancel::CancelToken;
+pub use capabilities::SyncPointCapabilities;
+use cond::PointCond;
 use core::ops::Deref;
 use core::ops::DerefMut;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use debug::LockSite;
+use debug::PointHolder;
+pub use error::Cancelled;
+pub use error::Poisoned;
+pub use error::TimedOut;
+pub use guard::SyncGuard;
+pub use hold::HoldOverrun;
+pub use hold::default_max_hold;
+#[doc(hidden)]
+pub use hold::parse_max_hold;
+pub use hold::set_default_max_hold;
+pub use hold::set_hold_overrun_callback;
+#[cfg(feature = "track-holders")]
+#[cfg_attr(docsrs, doc(cfg(feature = "track-holders")))]
+pub use holders::HeldPoint;
+#[cfg(feature = "track-holders")]
+#[cfg_attr(docsrs, doc(cfg(feature = "track-holders")))]
+pub use holders::Holder;
+#[cfg(feature = "track-holders")]
+#[cfg_attr(docsrs, doc(cfg(feature = "track-holders")))]
+pub use holders::dump_holders;
+pub use keyed::KeyedEntry;
+pub use keyed::KeyedSyncPoint;
+pub use keyed::key_hash;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use metrics::LockStats;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use metrics::MAX_STATS_SITES;
+use metrics::PointMetrics;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use metrics::SiteStats;
+use metrics::Stopwatch;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use metrics::SyncPointStats;
+pub use multi::SyncPointSet;
+pub use poison::PoisonPolicy;
+pub use reentrant::Reentrant;
+pub use reentrant::ReentrantGuard;
+pub use reset::reset_all;
+pub use rw::RwReadGuard;
+pub use rw::RwSyncPoint;
+pub use rw::RwSyncPointBeh;
+pub use rw::RwWriteGuard;
+use std::time::Instant;
+pub use striped::DEFAULT_STRIPES_LEN;
+pub use striped::StripedSyncPoint;
+#[doc(hidden)]
+pub use striped::SyncObject;
+#[doc(hidden)]
+pub use striped::SyncObjectOfPointer;
+#[doc(hidden)]
+pub use striped::SyncObjectOfValue;
+pub use transaction::SyncTransaction;
 
 use crate::cfg::cfg_async;
 use crate::cfg::cfg_not_async;
 
+cfg_not_async! {
+	/// The maximum time between checks of `CancelToken` while waiting
+	/// for the sync point in the synchronous version: the delay of noticing
+	/// the cancellation, the thread wakes up this often while waiting.
+	const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(1);
+
+	/// The maximum time between attempts of `try_lock` in the default
+	/// `SyncPointBeh::try_lock_for`.
+	const TRY_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+}
+
 cfg_async_or_sync! {
 	/// Implementation of the behavior for the used synchronization structure.
 	pub trait SyncPointBeh {
@@ -17,12 +113,56 @@ cfg_async_or_sync! {
 		#only_async {
 			/// Create a new hold lock.
 			fn new_lock(&self) -> impl core::future::Future<Output = Self::LockType<'_>> + Send;
+
+			/// Create a new hold lock, waiting no longer than `timeout`,
+			/// if the time is up, then return None.
+			///
+			/// By default `new_lock` is abandoned when the time is up.
+			fn try_lock_for(&self, timeout: Duration) -> impl core::future::Future<Output = Option<Self::LockType<'_>>> + Send {
+				let lock = self.new_lock();
+
+				async move { tokio::time::timeout(timeout, lock).await.ok() }
+			}
+
+			/// Create a new hold lock, waiting no longer than until `deadline`,
+			/// if the time is up, then return None.
+			fn try_lock_until(&self, deadline: Instant) -> impl core::future::Future<Output = Option<Self::LockType<'_>>> + Send {
+				self.try_lock_for(deadline.saturating_duration_since(Instant::now()))
+			}
 		}
 		/// This section of code is connected only if
 		/// the current library is synchronous.
 		#only_sync {
 			/// Create a new hold lock.
 			fn new_lock(&self) -> Self::LockType<'_>;
+
+			/// Create a new hold lock, waiting no longer than `timeout`,
+			/// if the time is up, then return None.
+			///
+			/// By default `try_lock` is repeated every millisecond until the time is up.
+			fn try_lock_for(&self, timeout: Duration) -> Option<Self::LockType<'_>> {
+				let deadline = match Instant::now().checked_add(timeout) {
+					Some(a) => a,
+					None => return Some(self.new_lock()),
+				};
+				loop {
+					if let Some(a) = self.try_lock() {
+						return Some(a);
+					}
+
+					let left = deadline.saturating_duration_since(Instant::now());
+					if left.is_zero() {
+						return None;
+					}
+					std::thread::sleep(left.min(TRY_LOCK_RETRY_INTERVAL));
+				}
+			}
+
+			/// Create a new hold lock, waiting no longer than until `deadline`,
+			/// if the time is up, then return None.
+			fn try_lock_until(&self, deadline: Instant) -> Option<Self::LockType<'_>> {
+				self.try_lock_for(deadline.saturating_duration_since(Instant::now()))
+			}
 		}
 
 		/// If the lock exists and is not released, then return None,
@@ -40,6 +180,16 @@ cfg_async_or_sync! {
 		/// The data type to modify, provided by the synchronization structure.
 		type DerefLockType;
 
+		/// Capabilities of the current synchronization structure.
+		///
+		/// By default only `try_lock` and locking with a time limit are reported,
+		/// which every structure provides.
+		const CAPABILITIES: SyncPointCapabilities = SyncPointCapabilities {
+			timed_lock: true,
+			try_lock: true,
+			..SyncPointCapabilities::NONE
+		};
+
 		/// Whether the current lock is active
 		#[cfg_attr(docsrs, doc(cfg( feature = "pl" )))]
 		#[cfg( all(
@@ -54,10 +204,32 @@ cfg_async_or_sync! {
 
 /// Universal synchronization point structure,
 /// combining various types of locks and working with them.
-#[repr(transparent)]
-pub struct SyncPoint<T> {
+pub struct SyncPoint<T>
+where
+	T: SyncPointBeh,
+{
 	/// Generalized structure for generating locks.
 	mutex_builder: T,
+	/// Name of the sync point for messages.
+	name: Option<&'static str>,
+	/// The thread (or task) holding the sync point (debug checks).
+	holder: PointHolder,
+	/// Threads (or tasks) waiting for a condition on the data (`wait_while`).
+	cond: PointCond,
+	/// Creation of the initial value of the sync point.
+	init: Option<fn() -> T::DerefLockType>,
+	/// The behavior after the code holding the sync point panicked.
+	poison_policy: PoisonPolicy,
+	/// The code holding the sync point panicked.
+	poisoned: AtomicBool,
+	/// The sync point is registered for `reset_all`.
+	registered: AtomicBool,
+	/// Registration of the sync point for `reset_all` on first lock (`with_registration`).
+	registration: Option<fn()>,
+	/// The budget of the time the sync point may be held.
+	max_hold: Option<Duration>,
+	/// Counters of locking of the sync point (the `metrics` feature).
+	metrics: PointMetrics,
 }
 
 impl<T> SyncPoint<T>
@@ -67,39 +239,718 @@ where
 	/// Structure creation.
 	#[inline]
 	pub const fn new(mutex_builder: T) -> Self {
-		Self { mutex_builder }
+		Self {
+			mutex_builder,
+			name: None,
+			holder: PointHolder::new(),
+			cond: PointCond::new(),
+			init: None,
+			poison_policy: PoisonPolicy::Ignore,
+			poisoned: AtomicBool::new(false),
+			registered: AtomicBool::new(false),
+			registration: None,
+			max_hold: None,
+			metrics: PointMetrics::new(),
+		}
+	}
+
+	/// Set the name of the sync point used in messages
+	/// (the name of the `sync_point` variable).
+	#[inline]
+	pub const fn with_name(mut self, name: &'static str) -> Self {
+		self.name = Some(name);
+		self
+	}
+
+	/// Name of the sync point used in messages.
+	#[inline]
+	pub const fn name(&self) -> &'static str {
+		match self.name {
+			Some(a) => a,
+			None => "<unnamed>",
+		}
+	}
+
+	/// The thread (or task) holding the sync point right now,
+	/// when and where it was locked.
+	#[cfg(feature = "track-holders")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "track-holders")))]
+	#[inline]
+	pub fn holder(&self) -> Option<Holder> {
+		holders::holder(self.addr())
+	}
+
+	/// Set the budget of the time the sync point may be held, the blocks
+	/// exceeding it are reported when the sync point is released
+	/// (`set_hold_overrun_callback`), a block that never releases it is not reported.
+	#[inline]
+	pub const fn with_max_hold(mut self, max_hold: Duration) -> Self {
+		self.max_hold = Some(max_hold);
+		self
+	}
+
+	/// The budget of the time the sync point may be held,
+	/// its own or the default one (`set_default_max_hold`).
+	#[inline]
+	pub fn max_hold(&self) -> Option<Duration> {
+		match self.max_hold {
+			Some(a) => Some(a),
+			None => default_max_hold(),
+		}
+	}
+
+	/// Snapshot of the counters of locking of the sync point,
+	/// together and for each place of the code (`sync!`) where it was locked.
+	///
+	/// Only the first `MAX_STATS_SITES` places are counted separately,
+	/// the others are counted only in `total`.
+	///
+	/// ```rust
+	///	use synchronized::sync;
+	///	use synchronized::sync_point;
+	///
+	///	sync_point!(COMB_SYNC: usize = 0);
+	///
+	///	for _ in 0..3 {
+	///		sync!(->COMB_SYNC(count) {
+	///			*count += 1;
+	///		});
+	///	}
+	///
+	///	let stats = COMB_SYNC.stats();
+	///	assert_eq!(stats.total.acquisitions, 3);
+	///	assert_eq!(stats.sites.len(), 1);
+	/// ```
+	#[cfg(feature = "metrics")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+	#[inline]
+	pub fn stats(&self) -> SyncPointStats {
+		self.metrics.stats()
+	}
+
+	/// Set the counters of locking of the sync point to zero.
+	#[cfg(feature = "metrics")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+	#[inline]
+	pub fn reset_stats(&self) {
+		self.metrics.reset()
+	}
+
+	/// Address identifying the sync point.
+	#[inline]
+	fn addr(&self) -> usize {
+		self as *const Self as usize
+	}
+
+	/// Set the creation of the initial value of the sync point
+	/// (used by `PoisonPolicy::Reset`).
+	#[inline]
+	pub const fn with_init(mut self, init: fn() -> T::DerefLockType) -> Self {
+		self.init = Some(init);
+		self
+	}
+
+	/// Set the registration of the sync point for `reset_all` done on its first lock,
+	/// `register` calls `SyncPoint::register` on the static (`sync_point`).
+	#[doc(hidden)]
+	#[inline]
+	pub const fn with_registration(mut self, register: fn()) -> Self {
+		self.registration = Some(register);
+		self
+	}
+
+	/// Set the behavior after the code holding the sync point panicked.
+	///
+	/// # Panics
+	///
+	/// If the policy is `PoisonPolicy::Reset`, but the initial value is not set (`with_init`).
+	#[inline]
+	pub const fn with_poison_policy(mut self, poison_policy: PoisonPolicy) -> Self {
+		if matches!(poison_policy, PoisonPolicy::Reset) && self.init.is_none() {
+			panic!("`PoisonPolicy::Reset` requires the initial value of the sync point");
+		}
+
+		self.poison_policy = poison_policy;
+		self
+	}
+
+	/// The behavior after the code holding the sync point panicked.
+	#[inline]
+	pub const fn poison_policy(&self) -> PoisonPolicy {
+		self.poison_policy
+	}
+
+	/// Whether the code holding the sync point panicked.
+	#[inline]
+	pub fn is_poisoned(&self) -> bool {
+		self.poisoned.load(Ordering::Acquire)
+	}
+
+	/// Mark the sync point as not poisoned.
+	#[inline]
+	pub fn clear_poison(&self) {
+		self.poisoned.store(false, Ordering::Release)
+	}
+
+	/// Mark the sync point as poisoned (the holding code panicked).
+	#[inline]
+	pub(crate) fn poison(&self) {
+		self.poisoned.store(true, Ordering::Release)
+	}
+
+	/// Wrapping of the acquired `lock` in `SyncGuard` applying the poisoning policy,
+	/// with `PoisonPolicy::Propagate` a poisoned sync point is released and `Poisoned` is returned.
+	fn acquired_checked<'a>(
+		&'a self,
+		lock: T::LockType<'a>,
+		site: LockSite,
+	) -> Result<SyncGuard<'a, T>, Poisoned> {
+		let mut lock = SyncGuard::new(self, lock, site);
+		if self.is_poisoned() {
+			match self.poison_policy {
+				PoisonPolicy::Ignore => {}
+				PoisonPolicy::Propagate => return Err(Poisoned),
+				PoisonPolicy::Reset => {
+					if let Some(init) = self.init {
+						*lock = init();
+					}
+					self.clear_poison();
+				}
+			}
+		}
+
+		Ok(lock)
+	}
+
+	/// Wrapping of the acquired `lock` in `SyncGuard` applying the poisoning policy.
+	///
+	/// # Panics
+	///
+	/// With `PoisonPolicy::Propagate` if the sync point is poisoned.
+	fn acquired<'a>(&'a self, lock: T::LockType<'a>, site: LockSite) -> SyncGuard<'a, T> {
+		match self.acquired_checked(lock, site) {
+			Ok(a) => a,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+	/// Check before waiting for the sync point at `site` that the current
+	/// thread (or task) does not hold it already, which would wait forever
+	/// (in debug builds or with the `debug-checks` feature),
+	/// and that the order of locking of named sync points is consistent
+	/// (with the `lockdep` feature).
+	///
+	/// # Panics
+	///
+	/// If the current thread (or task) holds the sync point
+	/// or the order of locking contradicts an order used earlier.
+	#[inline]
+	fn enter(&self, site: LockSite) {
+		self.holder.enter(self.name(), site);
+		if let Some(name) = self.name {
+			lockdep::enter(self.addr(), name, site);
+		}
+		#[cfg(feature = "pl-deadlock-detection")]
+		crate::deadlock::waiting(self.addr(), self.name(), site);
+	}
+
+	/// The waiting for the sync point started with `enter` ended without
+	/// locking it (the time is up or the waiting was cancelled).
+	#[inline]
+	fn abandoned(&self) {
+		#[cfg(feature = "pl-deadlock-detection")]
+		crate::deadlock::abandoned();
+	}
+
+	/// The initial value of the sync point is not set (`with_init`).
+	#[cold]
+	fn missing_init() -> ! {
+		panic!("the sync point has no initial value to reset to (`with_init`)")
+	}
+
+	cfg_not_async! {
+		/// Waiting for the sync point at `site`, with the `metrics` feature
+		/// the sync point is tried first to count the contention.
+		#[inline]
+		fn lock_raw(&self, site: LockSite) -> T::LockType<'_> {
+			if metrics::ENABLED && let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return a;
+			}
+
+			let wait = Stopwatch::start();
+			let lock = T::new_lock(&self.mutex_builder);
+			self.metrics.acquired(site, Some(wait.elapsed()));
+			lock
+		}
+
+		/// Waiting for the sync point at `site` with `lock` (with a timeout),
+		/// with the `metrics` feature the sync point is tried first to count the contention.
+		#[inline]
+		fn try_lock_raw<'a>(
+			&'a self,
+			site: LockSite,
+			lock: impl FnOnce() -> Option<T::LockType<'a>>,
+		) -> Option<T::LockType<'a>> {
+			if metrics::ENABLED && let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return Some(a);
+			}
+
+			let wait = Stopwatch::start();
+			let lock = lock();
+			match lock {
+				Some(_) => self.metrics.acquired(site, Some(wait.elapsed())),
+				None => self.metrics.try_lock_failed(site),
+			}
+			lock
+		}
+	}
+
+	cfg_async! {
+		/// Waiting for the sync point at `site`, with the `metrics` feature
+		/// the sync point is tried first to count the contention.
+		#[inline]
+		async fn lock_raw(&self, site: LockSite) -> T::LockType<'_> {
+			if metrics::ENABLED && let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return a;
+			}
+
+			let wait = Stopwatch::start();
+			let lock = T::new_lock(&self.mutex_builder).await;
+			self.metrics.acquired(site, Some(wait.elapsed()));
+			lock
+		}
+
+		/// Waiting for the sync point at `site` with `lock` (with a timeout),
+		/// with the `metrics` feature the sync point is tried first to count the contention.
+		#[inline]
+		async fn try_lock_raw<'a>(
+			&'a self,
+			site: LockSite,
+			lock: impl core::future::Future<Output = Option<T::LockType<'a>>>,
+		) -> Option<T::LockType<'a>> {
+			if metrics::ENABLED && let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return Some(a);
+			}
+
+			let wait = Stopwatch::start();
+			let lock = lock.await;
+			match lock {
+				Some(_) => self.metrics.acquired(site, Some(wait.elapsed())),
+				None => self.metrics.try_lock_failed(site),
+			}
+			lock
+		}
+	}
+
+	cfg_not_async! {
+		/// Replace the value of the sync point with the initial value
+		/// (the expression of `sync_point`) under the lock,
+		/// the sync point is no longer poisoned.
+		///
+		/// # Panics
+		///
+		/// If the initial value of the sync point is not set (`with_init`).
+		#[track_caller]
+		pub fn reset(&self) {
+			let Some(init) = self.init else {
+				Self::missing_init()
+			};
+
+			let site = LockSite::caller();
+			self.enter(site);
+			let mut lock = SyncGuard::new(self, self.lock_raw(site), site);
+			*lock = init();
+			self.clear_poison();
+		}
+	}
+
+	cfg_async! {
+		/// Replace the value of the sync point with the initial value
+		/// (the expression of `sync_point`) under the lock,
+		/// the sync point is no longer poisoned.
+		///
+		/// # Panics
+		///
+		/// If the initial value of the sync point is not set (`with_init`).
+		#[track_caller]
+		#[inline]
+		pub fn reset(&self) -> impl core::future::Future<Output = ()> {
+			self.reset_at(LockSite::caller())
+		}
+
+		/// `reset` locking the sync point at `site`.
+		async fn reset_at(&self, site: LockSite) {
+			let Some(init) = self.init else {
+				Self::missing_init()
+			};
+
+			self.enter(site);
+			let mut lock = SyncGuard::new(self, self.lock_raw(site).await, site);
+			*lock = init();
+			self.clear_poison();
+		}
+	}
+
+	cfg_not_async! {
+		/// Create a new hold lock, if the sync point is poisoned
+		/// with `PoisonPolicy::Propagate`, then return `Poisoned`.
+		#[track_caller]
+		#[inline]
+		pub fn lock_checked(&self) -> Result<SyncGuard<'_, T>, Poisoned> {
+			let site = LockSite::caller();
+			self.enter(site);
+			self.acquired_checked(self.lock_raw(site), site)
+		}
+	}
+
+	cfg_async! {
+		/// Create a new hold lock, if the sync point is poisoned
+		/// with `PoisonPolicy::Propagate`, then return `Poisoned`.
+		#[track_caller]
+		#[inline]
+		pub fn lock_checked(&self) -> impl core::future::Future<Output = Result<SyncGuard<'_, T>, Poisoned>> {
+			self.lock_checked_at(LockSite::caller())
+		}
+
+		/// `lock_checked` locking the sync point at `site`.
+		async fn lock_checked_at(&self, site: LockSite) -> Result<SyncGuard<'_, T>, Poisoned> {
+			self.enter(site);
+			self.acquired_checked(self.lock_raw(site).await, site)
+		}
 	}
 
 	cfg_not_async! {
 		/// Create a new hold lock.
+		#[track_caller]
 		#[inline]
-		pub fn new_lock(&self) -> T::LockType<'_> {
-			T::new_lock(&self.mutex_builder)
+		pub fn new_lock(&self) -> SyncGuard<'_, T> {
+			let site = LockSite::caller();
+			self.enter(site);
+			self.acquired(self.lock_raw(site), site)
 		}
 	}
 
 	cfg_async! {
 		/// Create a new hold lock.
+		#[track_caller]
+		#[inline]
+		pub fn new_lock(&self) -> impl core::future::Future<Output = SyncGuard<'_, T>> {
+			self.new_lock_at(LockSite::caller())
+		}
+
+		/// `new_lock` locking the sync point at `site`.
+		pub(crate) async fn new_lock_at(&self, site: LockSite) -> SyncGuard<'_, T> {
+			self.enter(site);
+			self.acquired(self.lock_raw(site).await, site)
+		}
+	}
+
+	cfg_not_async! {
+		/// Create a new hold lock, waiting no longer than `timeout`,
+		/// if the time is up, then return None.
+		#[track_caller]
+		#[inline]
+		pub fn try_lock_for(&self, timeout: Duration) -> Option<SyncGuard<'_, T>> {
+			let site = LockSite::caller();
+			self.enter(site);
+			match self.try_lock_raw(site, || T::try_lock_for(&self.mutex_builder, timeout)) {
+				Some(a) => Some(self.acquired(a, site)),
+				None => {
+					self.abandoned();
+					None
+				}
+			}
+		}
+
+		/// Create a new hold lock, waiting no longer than until `deadline`,
+		/// if the time is up, then return None.
+		#[track_caller]
 		#[inline]
-		pub async fn new_lock(&self) -> T::LockType<'_> {
-			T::new_lock(&self.mutex_builder).await
+		pub fn try_lock_until(&self, deadline: Instant) -> Option<SyncGuard<'_, T>> {
+			let site = LockSite::caller();
+			self.enter(site);
+			match self.try_lock_raw(site, || T::try_lock_until(&self.mutex_builder, deadline)) {
+				Some(a) => Some(self.acquired(a, site)),
+				None => {
+					self.abandoned();
+					None
+				}
+			}
 		}
 	}
 
+	cfg_async! {
+		/// Create a new hold lock, waiting no longer than `timeout`,
+		/// if the time is up, then return None.
+		#[track_caller]
+		#[inline]
+		pub fn try_lock_for(&self, timeout: Duration) -> impl core::future::Future<Output = Option<SyncGuard<'_, T>>> {
+			let site = LockSite::caller();
+			async move {
+				self.enter(site);
+				match self.try_lock_raw(site, T::try_lock_for(&self.mutex_builder, timeout)).await {
+					Some(a) => Some(self.acquired(a, site)),
+					None => {
+						self.abandoned();
+						None
+					}
+				}
+			}
+		}
+
+		/// Create a new hold lock, waiting no longer than until `deadline`,
+		/// if the time is up, then return None.
+		#[track_caller]
+		#[inline]
+		pub fn try_lock_until(&self, deadline: Instant) -> impl core::future::Future<Output = Option<SyncGuard<'_, T>>> {
+			let site = LockSite::caller();
+			async move {
+				self.enter(site);
+				match self.try_lock_raw(site, T::try_lock_until(&self.mutex_builder, deadline)).await {
+					Some(a) => Some(self.acquired(a, site)),
+					None => {
+						self.abandoned();
+						None
+					}
+				}
+			}
+		}
+	}
+
+	cfg_not_async! {
+		/// Create a new hold lock, the waiting is abandoned
+		/// if `token` is cancelled (or has already been cancelled).
+		///
+		/// The thread cannot wait for the release and the token at once,
+		/// it waits for the release in slices of 1ms (`try_lock_for`)
+		/// and checks the token between them, so the cancellation is noticed
+		/// up to 1ms later and a long wait wakes the thread up about
+		/// a thousand times a second.
+		#[track_caller]
+		pub fn lock_cancellable(&self, token: &CancelToken) -> Result<SyncGuard<'_, T>, Cancelled> {
+			let site = LockSite::caller();
+			if token.is_cancelled() {
+				return Err(Cancelled);
+			}
+			self.enter(site);
+			if let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return Ok(self.acquired(a, site));
+			}
+
+			let wait = Stopwatch::start();
+			loop {
+				if let Some(a) = T::try_lock_for(&self.mutex_builder, CANCEL_CHECK_INTERVAL) {
+					self.metrics.acquired(site, Some(wait.elapsed()));
+					return Ok(self.acquired(a, site));
+				}
+
+				if token.is_cancelled() {
+					self.abandoned();
+					return Err(Cancelled);
+				}
+			}
+		}
+	}
+
+	cfg_async! {
+		/// Create a new hold lock, the waiting is abandoned
+		/// if `token` is cancelled (or has already been cancelled).
+		#[track_caller]
+		#[inline]
+		pub fn lock_cancellable<'a>(
+			&'a self,
+			token: &'a CancelToken,
+		) -> impl core::future::Future<Output = Result<SyncGuard<'a, T>, Cancelled>> {
+			self.lock_cancellable_at(token, LockSite::caller())
+		}
+
+		/// `lock_cancellable` locking the sync point at `site`.
+		async fn lock_cancellable_at<'a>(
+			&'a self,
+			token: &'a CancelToken,
+			site: LockSite,
+		) -> Result<SyncGuard<'a, T>, Cancelled> {
+			if token.is_cancelled() {
+				return Err(Cancelled);
+			}
+			self.enter(site);
+			if let Some(a) = T::try_lock(&self.mutex_builder) {
+				self.metrics.acquired(site, None);
+				return Ok(self.acquired(a, site));
+			}
+
+			let wait = Stopwatch::start();
+			let mut lock = core::pin::pin!(T::new_lock(&self.mutex_builder));
+			let mut cancelled = core::pin::pin!(token.cancelled());
+			core::future::poll_fn(|cx| {
+				if let core::task::Poll::Ready(a) = lock.as_mut().poll(cx) {
+					self.metrics.acquired(site, Some(wait.elapsed()));
+					return core::task::Poll::Ready(Ok(self.acquired(a, site)));
+				}
+				if cancelled.as_mut().poll(cx).is_ready() {
+					self.abandoned();
+					return core::task::Poll::Ready(Err(Cancelled));
+				}
+
+				core::task::Poll::Pending
+			})
+			.await
+		}
+	}
+
+	cfg_not_async! {
+		/// Release the held `lock` and wait for a notification
+		/// (`notify_one`, `notify_all`) while `condition` is true.
+		///
+		/// The lock is taken again before each check of `condition`
+		/// and is returned held when `condition` becomes false.
+		#[track_caller]
+		pub fn wait_while<'a>(
+			&'a self,
+			mut lock: SyncGuard<'a, T>,
+			mut condition: impl FnMut(&mut T::DerefLockType) -> bool,
+		) -> SyncGuard<'a, T> {
+			while condition(&mut lock) {
+				let generation = self.cond.generation();
+				self.unlock(lock);
+				self.cond.wait(generation);
+
+				lock = self.new_lock();
+			}
+
+			lock
+		}
+	}
+
+	cfg_async! {
+		/// Release the held `lock` and wait for a notification
+		/// (`notify_one`, `notify_all`) while `condition` is true.
+		///
+		/// The lock is taken again before each check of `condition`
+		/// and is returned held when `condition` becomes false.
+		#[track_caller]
+		#[inline]
+		pub fn wait_while<'a>(
+			&'a self,
+			lock: SyncGuard<'a, T>,
+			condition: impl FnMut(&mut T::DerefLockType) -> bool,
+		) -> impl core::future::Future<Output = SyncGuard<'a, T>> {
+			self.wait_while_at(lock, condition, LockSite::caller())
+		}
+
+		/// `wait_while` locking the sync point again at `site`.
+		async fn wait_while_at<'a>(
+			&'a self,
+			mut lock: SyncGuard<'a, T>,
+			mut condition: impl FnMut(&mut T::DerefLockType) -> bool,
+			site: LockSite,
+		) -> SyncGuard<'a, T> {
+			while condition(&mut lock) {
+				let mut notified = core::pin::pin!(self.cond.notified());
+				// Registering before releasing so as not to miss the notification.
+				notified.as_mut().enable();
+				self.unlock(lock);
+				notified.await;
+
+				lock = self.new_lock_at(site).await;
+			}
+
+			lock
+		}
+	}
+
+	/// Wake up one thread (or task) waiting in `wait_while`.
+	#[inline]
+	pub fn notify_one(&self) {
+		self.cond.notify_one()
+	}
+
+	/// Wake up all threads (or tasks) waiting in `wait_while`.
+	#[inline]
+	pub fn notify_all(&self) {
+		self.cond.notify_all()
+	}
+
 	/// If the lock exists and is not released, then return None,
 	/// if there is no lock, then create it and return Some.
+	#[track_caller]
 	#[inline]
-	pub fn try_lock(&self) -> Option<T::LockType<'_>> {
-		T::try_lock(&self.mutex_builder)
+	pub fn try_lock(&self) -> Option<SyncGuard<'_, T>> {
+		let site = LockSite::caller();
+		match T::try_lock(&self.mutex_builder) {
+			Some(a) => {
+				self.metrics.acquired(site, None);
+				Some(self.acquired(a, site))
+			}
+			None => {
+				self.metrics.try_lock_failed(site);
+				None
+			}
+		}
 	}
 
 	/// Destroy the blocking structure and remove the lock
-	/// (usually always involves just a drop).
+	/// (the same as `SyncGuard::unlock`).
+	#[inline]
+	pub fn unlock(&self, lock: SyncGuard<'_, T>) {
+		debug_assert!(
+			core::ptr::eq(self, lock.point()),
+			"the lock is held on another sync point"
+		);
+
+		lock.unlock()
+	}
+
+	/// The sync point was locked at `site` (`SyncGuard` creation).
+	#[inline]
+	pub(crate) fn locked_raw(&self, site: LockSite) {
+		if let Some(register) = self.registration
+			&& !self.registered.load(Ordering::Relaxed)
+		{
+			register();
+		}
+		self.holder.acquired(site);
+		if let Some(name) = self.name {
+			lockdep::acquired(self.addr(), name, site);
+		}
+		#[cfg(feature = "pl-deadlock-detection")]
+		crate::deadlock::acquired(self.addr(), self.name(), site);
+		#[cfg(feature = "track-holders")]
+		holders::acquired(self.addr(), self.name(), site);
+	}
+
+	/// Release the lock of the synchronization structure (`SyncGuard` drop).
 	#[inline]
-	pub fn unlock(&self, lock: T::LockType<'_>) {
+	pub(crate) fn unlock_raw(&self, lock: T::LockType<'_>) {
+		self.holder.released();
+		if self.name.is_some() {
+			lockdep::released(self.addr());
+		}
+		#[cfg(feature = "pl-deadlock-detection")]
+		crate::deadlock::released(self.addr());
+		#[cfg(feature = "track-holders")]
+		holders::released(self.addr());
 		T::unlock(&self.mutex_builder, lock)
 	}
 
+	/// Capabilities of the synchronization structure used by this point,
+	/// poisoning is tracked by the sync point itself with any structure.
+	pub const CAPABILITIES: SyncPointCapabilities = SyncPointCapabilities {
+		poisoning: true,
+		..T::CAPABILITIES
+	};
+
+	/// Capabilities of the synchronization structure used by this point.
+	#[inline]
+	pub const fn capabilities(&self) -> SyncPointCapabilities {
+		Self::CAPABILITIES
+	}
+
 	/// Whether the current lock is active
 	#[inline]
 	#[cfg_attr(docsrs, doc(cfg(feature = "pl")))]
@@ -108,3 +959,164 @@ where
 		T::is_lock(&self.mutex_builder)
 	}
 }
+
+impl<T> SyncPoint<T>
+where
+	T: SyncPointBeh + Sync + 'static,
+{
+	/// Add the sync point to the points reset by `reset_all`
+	/// (done on the first lock of the sync points of `sync_point`),
+	/// sync points without the initial value (`with_init`) are not added.
+	#[inline]
+	pub fn register(&'static self) -> &'static Self {
+		if self.init.is_some()
+			&& !self.registered.load(Ordering::Relaxed)
+			&& !self.registered.swap(true, Ordering::AcqRel)
+		{
+			reset::register(self);
+		}
+
+		self
+	}
+}
+
+cfg_not_async! {
+	impl<T> reset::ResetPoint for SyncPoint<T>
+	where
+		T: SyncPointBeh + Sync,
+	{
+		#[inline]
+		fn reset_point(&self) {
+			self.reset()
+		}
+	}
+}
+
+cfg_async! {
+	impl<T> reset::ResetPoint for SyncPoint<T>
+	where
+		T: SyncPointBeh + Sync,
+	{
+		#[inline]
+		fn reset_point(&self) -> core::pin::Pin<std::boxed::Box<dyn core::future::Future<Output = ()> + Send + '_>> {
+			std::boxed::Box::pin(self.reset())
+		}
+	}
+}
+
+impl<T> SyncPoint<T>
+where
+	T: SyncPointBeh,
+{
+	cfg_not_async! {
+		/// Execute `f` with the value of the sync point held.
+		#[track_caller]
+		#[inline]
+		pub fn with<R>(&self, f: impl FnOnce(&mut T::DerefLockType) -> R) -> R {
+			let mut lock = self.new_lock();
+			let result = f(&mut lock);
+			self.unlock(lock);
+
+			result
+		}
+
+		/// Change the value of the sync point with `f`.
+		#[track_caller]
+		#[inline]
+		pub fn update(&self, f: impl FnOnce(&mut T::DerefLockType)) {
+			self.with(f)
+		}
+
+		/// Replace the value of the sync point, return the old value.
+		#[track_caller]
+		#[inline]
+		pub fn replace(&self, value: T::DerefLockType) -> T::DerefLockType {
+			self.with(|a| core::mem::replace(a, value))
+		}
+
+		/// Take the value of the sync point, leaving `Default::default()` in its place.
+		#[track_caller]
+		#[inline]
+		pub fn take(&self) -> T::DerefLockType
+		where
+			T::DerefLockType: Default,
+		{
+			self.with(core::mem::take)
+		}
+
+		/// A copy of the value of the sync point.
+		#[track_caller]
+		#[inline]
+		pub fn get_cloned(&self) -> T::DerefLockType
+		where
+			T::DerefLockType: Clone,
+		{
+			self.with(|a| a.clone())
+		}
+	}
+
+	cfg_async! {
+		/// Execute `f` with the value of the sync point held.
+		#[track_caller]
+		#[inline]
+		pub fn with<R>(&self, f: impl FnOnce(&mut T::DerefLockType) -> R) -> impl core::future::Future<Output = R> {
+			self.with_at(f, LockSite::caller())
+		}
+
+		/// `with` locking the sync point at `site`.
+		async fn with_at<R>(&self, f: impl FnOnce(&mut T::DerefLockType) -> R, site: LockSite) -> R {
+			let mut lock = self.new_lock_at(site).await;
+			let result = f(&mut lock);
+			self.unlock(lock);
+
+			result
+		}
+
+		/// Change the value of the sync point with `f`.
+		#[track_caller]
+		#[inline]
+		pub fn update(&self, f: impl FnOnce(&mut T::DerefLockType)) -> impl core::future::Future<Output = ()> {
+			self.with_at(f, LockSite::caller())
+		}
+
+		/// Replace the value of the sync point, return the old value.
+		#[track_caller]
+		#[inline]
+		pub fn replace(&self, value: T::DerefLockType) -> impl core::future::Future<Output = T::DerefLockType> {
+			self.with_at(|a| core::mem::replace(a, value), LockSite::caller())
+		}
+
+		/// Take the value of the sync point, leaving `Default::default()` in its place.
+		#[track_caller]
+		#[inline]
+		pub fn take(&self) -> impl core::future::Future<Output = T::DerefLockType>
+		where
+			T::DerefLockType: Default,
+		{
+			self.with_at(core::mem::take, LockSite::caller())
+		}
+
+		/// A copy of the value of the sync point.
+		#[track_caller]
+		#[inline]
+		pub fn get_cloned(&self) -> impl core::future::Future<Output = T::DerefLockType>
+		where
+			T::DerefLockType: Clone,
+		{
+			self.with_at(|a| a.clone(), LockSite::caller())
+		}
+	}
+
+	/// Execute `f` with the value of the sync point held,
+	/// only if the sync point is not locked right now (without waiting),
+	/// otherwise return None.
+	#[track_caller]
+	#[inline]
+	pub fn with_try<R>(&self, f: impl FnOnce(&mut T::DerefLockType) -> R) -> Option<R> {
+		let mut lock = self.try_lock()?;
+		let result = f(&mut lock);
+		self.unlock(lock);
+
+		Some(result)
+	}
+}
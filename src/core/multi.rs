@@ -0,0 +1,178 @@
+//! Locking of several sync points at once in a globally consistent order.
+
+use crate::core::SyncGuard;
+use crate::core::SyncPoint;
+use crate::core::SyncPointBeh;
+use crate::core::r#async::cfg_async_or_sync;
+
+cfg_async_or_sync! {
+	/// A set of sync points (a tuple of references to them) that are locked together.
+	///
+	/// The points are always locked in the order of their addresses and released
+	/// in the reverse order, so two blocks locking the same points in a different
+	/// order cannot deadlock each other.
+	pub trait SyncPointSet {
+		/// This section of code is connected only if
+		/// the current library is asynchronous.
+		#only_async {
+			/// Create new hold locks of all sync points.
+			fn lock_all(self) -> impl core::future::Future<Output = Self::LockType>;
+		}
+		/// This section of code is connected only if
+		/// the current library is synchronous.
+		#only_sync {
+			/// Create new hold locks of all sync points.
+			fn lock_all(self) -> Self::LockType;
+		}
+
+		/// Release the locks of all sync points in the reverse order.
+		fn unlock_all(self, locks: Self::LockType);
+
+		/// Locks of all sync points, nested pairs in the order of the points,
+		/// for example `(A, (B, ()))`.
+		type LockType;
+	}
+}
+
+/// Nested pairs of the listed values (or patterns), for example `(a, (b, ()))`.
+macro_rules! nested {
+	[] => { () };
+	[ $first:ident $(, $rest:ident)* ] => {
+		($first, nested![ $($rest),* ])
+	};
+}
+
+/// Nested pairs of the listed types, for example `(A, (B, ()))`.
+macro_rules! nested_ty {
+	[] => { () };
+	[ $first:ty $(, $rest:ty)* ] => {
+		($first, nested_ty![ $($rest),* ])
+	};
+}
+
+/// The order in which the sync points with addresses `addrs` must be locked.
+///
+/// # Panics
+///
+/// If the same sync point is listed more than once (it would never be locked).
+fn lock_order<const N: usize>(addrs: [usize; N]) -> [usize; N] {
+	let mut order = [0; N];
+	for (i, a) in order.iter_mut().enumerate() {
+		*a = i;
+	}
+	order.sort_unstable_by_key(|&i| addrs[i]);
+
+	for pair in order.windows(2) {
+		if addrs[pair[0]] == addrs[pair[1]] {
+			panic!("the same sync point is listed more than once");
+		}
+	}
+
+	order
+}
+
+/// Get the lock created by `lock_all`.
+#[inline]
+fn locked<L>(lock: Option<L>) -> L {
+	match lock {
+		Some(a) => a,
+		None => unreachable!(),
+	}
+}
+
+/// Implementation of `SyncPointSet` for a tuple of references to sync points.
+macro_rules! impl_sync_point_set {
+	[ $( ($t:ident, $i:tt, $l:ident) ),+ ] => {
+		cfg_async_or_sync! {
+			impl['a, $($t: SyncPointBeh),+] SyncPointSet for ( $(&'a SyncPoint<$t>,)+ ) {
+				/// This section of code is connected only if
+				/// the current library is asynchronous.
+				#only_async {
+					#[track_caller]
+					fn lock_all(self) -> impl core::future::Future<Output = Self::LockType> {
+						let site = crate::core::debug::LockSite::caller();
+						async move {
+							let mut locks = ( $(None::<SyncGuard<'a, $t>>,)+ );
+							let addrs = [ $(self.$i as *const SyncPoint<$t> as usize),+ ];
+							for i in lock_order(addrs) {
+								match i {
+									$($i => locks.$i = Some(self.$i.new_lock_at(site).await),)+
+									_ => unreachable!(),
+								}
+							}
+
+							let ( $($l,)+ ) = ( $(locked(locks.$i),)+ );
+							nested![ $($l),+ ]
+						}
+					}
+				}
+				/// This section of code is connected only if
+				/// the current library is synchronous.
+				#only_sync {
+					#[track_caller]
+					fn lock_all(self) -> Self::LockType {
+						let mut locks = ( $(None::<SyncGuard<'a, $t>>,)+ );
+						let addrs = [ $(self.$i as *const SyncPoint<$t> as usize),+ ];
+						for i in lock_order(addrs) {
+							match i {
+								$($i => locks.$i = Some(self.$i.new_lock()),)+
+								_ => unreachable!(),
+							}
+						}
+
+						let ( $($l,)+ ) = ( $(locked(locks.$i),)+ );
+						nested![ $($l),+ ]
+					}
+				}
+
+				fn unlock_all(self, locks: Self::LockType) {
+					let nested![ $($l),+ ] = locks;
+					let mut locks = ( $(Some($l),)+ );
+					let addrs = [ $(self.$i as *const SyncPoint<$t> as usize),+ ];
+					for i in lock_order(addrs).into_iter().rev() {
+						match i {
+							$($i => if let Some(a) = locks.$i.take() {
+								self.$i.unlock(a);
+							},)+
+							_ => unreachable!(),
+						}
+					}
+				}
+
+				type LockType = nested_ty![ $(SyncGuard<'a, $t>),+ ];
+			}
+		}
+	};
+}
+
+impl_sync_point_set![(A, 0, a), (B, 1, b)];
+impl_sync_point_set![(A, 0, a), (B, 1, b), (C, 2, c)];
+impl_sync_point_set![(A, 0, a), (B, 1, b), (C, 2, c), (D, 3, d)];
+impl_sync_point_set![(A, 0, a), (B, 1, b), (C, 2, c), (D, 3, d), (E, 4, e)];
+impl_sync_point_set![
+	(A, 0, a),
+	(B, 1, b),
+	(C, 2, c),
+	(D, 3, d),
+	(E, 4, e),
+	(F, 5, f)
+];
+impl_sync_point_set![
+	(A, 0, a),
+	(B, 1, b),
+	(C, 2, c),
+	(D, 3, d),
+	(E, 4, e),
+	(F, 5, f),
+	(G, 6, g)
+];
+impl_sync_point_set![
+	(A, 0, a),
+	(B, 1, b),
+	(C, 2, c),
+	(D, 3, d),
+	(E, 4, e),
+	(F, 5, f),
+	(G, 6, g),
+	(H, 7, h)
+];
@@ -0,0 +1,19 @@
+//! What happens to a sync point after the code holding it panicked.
+
+/// The behavior of the sync point after the code holding it panicked
+/// (the sync point is poisoned).
+///
+/// Poisoning is tracked by `SyncPoint` itself, so it behaves the same
+/// with every synchronization primitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PoisonPolicy {
+	/// Poisoning is ignored, the next holder sees the value as the panic left it.
+	#[default]
+	Ignore,
+	/// Locking a poisoned sync point panics (or returns `Poisoned` from `lock_checked`)
+	/// until `clear_poison` is called.
+	Propagate,
+	/// The value of a poisoned sync point is replaced by its initial value
+	/// on the next lock.
+	Reset,
+}
@@ -0,0 +1,222 @@
+//! Locking of sync points that does not wait for the current thread (or task)
+//! if it already holds the sync point.
+
+use crate::cfg::cfg_async;
+use crate::cfg::cfg_not_async;
+use crate::core::SyncGuard;
+use crate::core::SyncPoint;
+use crate::core::SyncPointBeh;
+
+/// Locking of a sync point that is skipped if the current thread (or task)
+/// already holds the sync point through `Reentrant`.
+///
+/// Only locks taken through `Reentrant` are taken into account, locking
+/// a sync point held by a plain lock of the same thread (or task) still waits for itself.
+/// The value of the sync point is not available through `ReentrantGuard`,
+/// so code holding the sync point several times never gets
+/// two mutable references to the value.
+pub struct Reentrant<'a, T>
+where
+	T: SyncPointBeh,
+{
+	point: &'a SyncPoint<T>,
+}
+
+impl<'a, T> Reentrant<'a, T>
+where
+	T: SyncPointBeh,
+{
+	/// Reentrant locking of `point`.
+	#[inline]
+	pub const fn new(point: &'a SyncPoint<T>) -> Self {
+		Self { point }
+	}
+
+	cfg_not_async! {
+		/// Create a new hold lock, nothing is locked if the current thread
+		/// already holds the sync point through `Reentrant`.
+		#[track_caller]
+		pub fn new_lock(&self) -> ReentrantGuard<'a, T> {
+			let point = self.point.addr();
+			if held::contains(point) {
+				return ReentrantGuard { point, lock: None };
+			}
+
+			let lock = self.point.new_lock();
+			held::push(point);
+
+			ReentrantGuard { point, lock: Some(lock) }
+		}
+	}
+
+	cfg_async! {
+		/// Create a new hold lock, nothing is locked if the current task
+		/// already holds the sync point through `Reentrant`.
+		#[track_caller]
+		pub fn new_lock(&self) -> impl core::future::Future<Output = ReentrantGuard<'a, T>> {
+			let point = self.point;
+			let site = crate::core::debug::LockSite::caller();
+
+			async move {
+				let addr = point.addr();
+				if held::contains(addr) {
+					return ReentrantGuard { point: addr, lock: None };
+				}
+
+				let lock = point.new_lock_at(site).await;
+				held::push(addr);
+
+				ReentrantGuard { point: addr, lock: Some(lock) }
+			}
+		}
+	}
+
+	/// Release the lock created by `new_lock` (the same as drop),
+	/// the sync point is released if this lock was the one that locked it.
+	#[inline]
+	pub fn unlock(&self, lock: ReentrantGuard<'a, T>) {
+		debug_assert_eq!(
+			self.point.addr(),
+			lock.point,
+			"the lock is held on another sync point"
+		);
+
+		drop(lock)
+	}
+}
+
+/// A lock created by `Reentrant`, the sync point is released on drop
+/// if this lock was the one that locked it.
+pub struct ReentrantGuard<'a, T>
+where
+	T: SyncPointBeh,
+{
+	point: usize,
+	lock: Option<SyncGuard<'a, T>>,
+}
+
+impl<T> Drop for ReentrantGuard<'_, T>
+where
+	T: SyncPointBeh,
+{
+	fn drop(&mut self) {
+		if let Some(lock) = self.lock.take() {
+			held::remove(self.point);
+			drop(lock);
+		}
+	}
+}
+
+#[cfg(not(all(feature = "async", not(feature = "pl"), not(feature = "std"))))]
+mod held {
+	extern crate alloc;
+	extern crate std;
+
+	use alloc::vec::Vec;
+	use core::cell::RefCell;
+
+	std::thread_local! {
+		/// Sync points held by the current thread through `Reentrant`.
+		static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+	}
+
+	pub(super) fn contains(point: usize) -> bool {
+		HELD.with_borrow(|a| a.contains(&point))
+	}
+
+	pub(super) fn push(point: usize) {
+		HELD.with_borrow_mut(|a| a.push(point));
+	}
+
+	pub(super) fn remove(point: usize) {
+		HELD.with_borrow_mut(|a| {
+			if let Some(i) = a.iter().rposition(|a| *a == point) {
+				a.remove(i);
+			}
+		});
+	}
+}
+
+#[cfg(all(feature = "async", not(feature = "pl"), not(feature = "std")))]
+mod held {
+	extern crate alloc;
+	extern crate std;
+
+	use alloc::vec::Vec;
+	use std::collections::HashMap;
+	use std::sync::Mutex;
+	use std::sync::MutexGuard;
+	use std::thread::ThreadId;
+
+	/// The task holding sync points, asynchronous code running outside
+	/// of a task (`block_on`) stays on its thread.
+	#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+	enum Holder {
+		Task(tokio::task::Id),
+		Thread(ThreadId),
+	}
+
+	impl Holder {
+		fn current() -> Self {
+			match tokio::task::try_id() {
+				Some(a) => Self::Task(a),
+				None => Self::Thread(std::thread::current().id()),
+			}
+		}
+	}
+
+	/// Sync points held by each task through `Reentrant`,
+	/// a task can move between threads while holding them.
+	static HELD: Mutex<Option<HashMap<Holder, Vec<usize>>>> = Mutex::new(None);
+
+	fn lock() -> MutexGuard<'static, Option<HashMap<Holder, Vec<usize>>>> {
+		match HELD.lock() {
+			Ok(a) => a,
+			Err(e) => e.into_inner(),
+		}
+	}
+
+	pub(super) fn contains(point: usize) -> bool {
+		lock()
+			.as_ref()
+			.and_then(|a| a.get(&Holder::current()))
+			.is_some_and(|a| a.contains(&point))
+	}
+
+	pub(super) fn push(point: usize) {
+		lock()
+			.get_or_insert_with(HashMap::new)
+			.entry(Holder::current())
+			.or_default()
+			.push(point);
+	}
+
+	pub(super) fn remove(point: usize) {
+		let mut lock = lock();
+		let Some(holders) = lock.as_mut() else {
+			return;
+		};
+
+		// The lock can be dropped by another task than the one that created it.
+		let current = Holder::current();
+		let holder = match holders.get(&current) {
+			Some(a) if a.contains(&point) => Some(current),
+			_ => holders
+				.iter()
+				.find(|(_, a)| a.contains(&point))
+				.map(|(a, _)| *a),
+		};
+		let Some(holder) = holder else {
+			return;
+		};
+
+		if let Some(held) = holders.get_mut(&holder) {
+			if let Some(i) = held.iter().rposition(|a| *a == point) {
+				held.remove(i);
+			}
+			if held.is_empty() {
+				holders.remove(&holder);
+			}
+		}
+	}
+}
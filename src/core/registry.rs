@@ -0,0 +1,131 @@
+//! Type-indexed registry of synchronization points.
+//!
+//! A `static` cannot depend on the generic parameters of the function
+//! in which it is declared, so synchronization points for generic code
+//! are created on first use and stored in the registry under
+//! the `TypeId` of the key type.
+
+extern crate alloc;
+extern crate std;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::collections::btree_map;
+use core::any::Any;
+use core::any::TypeId;
+use core::sync::atomic::AtomicPtr;
+use core::sync::atomic::Ordering;
+use std::sync::RwLock;
+
+/// A value stored in the registry under the type `key`.
+struct Entry {
+	key: TypeId,
+	value: &'static (dyn Any + Send + Sync),
+}
+
+/// All synchronization points created through the registry.
+static REGISTRY: RwLock<BTreeMap<TypeId, &'static Entry>> = RwLock::new(BTreeMap::new());
+
+/// Get the entry stored under the type `K`,
+/// if there is no entry yet, then create it using `init`.
+fn get_or_insert<K, P>(init: impl FnOnce() -> P) -> &'static Entry
+where
+	K: ?Sized + 'static,
+	P: Send + Sync + 'static,
+{
+	let key = TypeId::of::<K>();
+	let entry = match REGISTRY.read() {
+		Ok(a) => a.get(&key).copied(),
+		Err(e) => e.into_inner().get(&key).copied(),
+	};
+	if let Some(a) = entry {
+		return a;
+	}
+
+	// The value is created without holding the registry,
+	// `init` can use the registry itself.
+	let value = init();
+	let mut registry = match REGISTRY.write() {
+		Ok(a) => a,
+		Err(e) => e.into_inner(),
+	};
+	match registry.entry(key) {
+		// Another thread was first, `value` is dropped.
+		btree_map::Entry::Occupied(a) => a.get(),
+		btree_map::Entry::Vacant(a) => a.insert(Box::leak(Box::new(Entry {
+			key,
+			value: Box::leak(Box::new(value)),
+		}))),
+	}
+}
+
+/// The value of `entry` as `P`.
+fn downcast<P: 'static>(entry: &'static Entry) -> &'static P {
+	match entry.value.downcast_ref() {
+		Some(a) => a,
+		None => panic!("the registry already contains a value of another type for this key"),
+	}
+}
+
+/// Get the value stored under the type `K`,
+/// if there is no value yet, then create it using `init`.
+///
+/// The created value lives until the end of the program.
+/// `init` is called without holding the registry, if several threads
+/// create the value at the same time, only one of the values is kept.
+///
+/// # Panics
+///
+/// If a value of a type other than `P` is already stored under the type `K`.
+pub fn get_or_init<K, P>(init: impl FnOnce() -> P) -> &'static P
+where
+	K: ?Sized + 'static,
+	P: Send + Sync + 'static,
+{
+	downcast(get_or_insert::<K, P>(init))
+}
+
+/// The last value looked up in the registry at one place of the code,
+/// allows not to look into the registry again while the key type does not change.
+pub struct SiteCache(AtomicPtr<Entry>);
+
+impl SiteCache {
+	/// An empty cache.
+	#[inline]
+	pub const fn new() -> Self {
+		Self(AtomicPtr::new(core::ptr::null_mut()))
+	}
+
+	/// Same as `get_or_init`, but the registry is not used
+	/// if the value for the type `K` was the last one looked up through this cache.
+	///
+	/// # Panics
+	///
+	/// If a value of a type other than `P` is already stored under the type `K`.
+	pub fn get_or_init<K, P>(&self, init: impl FnOnce() -> P) -> &'static P
+	where
+		K: ?Sized + 'static,
+		P: Send + Sync + 'static,
+	{
+		// SAFETY: only entries leaked by `get_or_insert` are stored in the cache.
+		let cached = unsafe { self.0.load(Ordering::Acquire).as_ref() };
+		if let Some(entry) = cached
+			&& entry.key == TypeId::of::<K>()
+		{
+			return downcast(entry);
+		}
+
+		let entry = get_or_insert::<K, P>(init);
+		self.0
+			.store(entry as *const Entry as *mut Entry, Ordering::Release);
+
+		downcast(entry)
+	}
+}
+
+impl Default for SiteCache {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,75 @@
+//! Resetting sync points to their initial value, for example,
+//! to isolate tests sharing the global state of `sync_point`.
+
+extern crate alloc;
+extern crate std;
+
+use crate::cfg::cfg_async;
+use crate::cfg::cfg_not_async;
+use alloc::vec::Vec;
+use std::sync::Mutex;
+
+cfg_not_async! {
+	/// Sync point whose value can be reset to the initial value.
+	pub(crate) trait ResetPoint: Sync {
+		/// Reset the value to the initial value (`SyncPoint::reset`).
+		fn reset_point(&self);
+	}
+}
+
+cfg_async! {
+	use alloc::boxed::Box;
+	use core::future::Future;
+	use core::pin::Pin;
+
+	/// Sync point whose value can be reset to the initial value.
+	pub(crate) trait ResetPoint: Sync {
+		/// Reset the value to the initial value (`SyncPoint::reset`).
+		fn reset_point(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+	}
+}
+
+/// All sync points registered for `reset_all` (`SyncPoint::register`).
+static POINTS: Mutex<Vec<&'static dyn ResetPoint>> = Mutex::new(Vec::new());
+
+/// Add the sync point to the points reset by `reset_all`.
+pub(crate) fn register(point: &'static dyn ResetPoint) {
+	match POINTS.lock() {
+		Ok(mut a) => a.push(point),
+		Err(e) => e.into_inner().push(point),
+	}
+}
+
+/// All registered sync points, the registry is not held while they are reset.
+fn registered() -> Vec<&'static dyn ResetPoint> {
+	match POINTS.lock() {
+		Ok(a) => a.clone(),
+		Err(e) => e.into_inner().clone(),
+	}
+}
+
+cfg_not_async! {
+	/// Reset all sync points of `sync_point` (and of `sync!` blocks) locked
+	/// at least once to their initial value, for example,
+	/// at the beginning of each test sharing them.
+	///
+	/// Sync points that have not been locked yet still have their initial value.
+	pub fn reset_all() {
+		for point in registered() {
+			point.reset_point();
+		}
+	}
+}
+
+cfg_async! {
+	/// Reset all sync points of `sync_point` (and of `sync!` blocks) locked
+	/// at least once to their initial value, for example,
+	/// at the beginning of each test sharing them.
+	///
+	/// Sync points that have not been locked yet still have their initial value.
+	pub async fn reset_all() {
+		for point in registered() {
+			point.reset_point().await;
+		}
+	}
+}
@@ -0,0 +1,315 @@
+//! Sync points whose value is shared by readers and held exclusively by writers.
+
+use crate::cfg::cfg_async;
+use crate::cfg::cfg_not_async;
+use crate::core::r#async::cfg_async_or_sync;
+use crate::core::debug::LockSite;
+use crate::core::debug::PointHolder;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+cfg_async_or_sync! {
+	/// Implementation of the behavior for the used synchronization structure
+	/// with shared (read) and exclusive (write) locks.
+	pub trait RwSyncPointBeh {
+		/// This section of code is connected only if
+		/// the current library is asynchronous.
+		#only_async {
+			/// Create a new exclusive lock.
+			fn write(&self) -> impl core::future::Future<Output = Self::WriteLockType<'_>> + Send;
+
+			/// Create a new shared lock.
+			fn read(&self) -> impl core::future::Future<Output = Self::ReadLockType<'_>> + Send;
+		}
+		/// This section of code is connected only if
+		/// the current library is synchronous.
+		#only_sync {
+			/// Create a new exclusive lock.
+			fn write(&self) -> Self::WriteLockType<'_>;
+
+			/// Create a new shared lock.
+			fn read(&self) -> Self::ReadLockType<'_>;
+		}
+
+		/// If the exclusive lock cannot be created without waiting, then return None.
+		fn try_write(&self) -> Option<Self::WriteLockType<'_>>;
+
+		/// If the shared lock cannot be created without waiting, then return None.
+		fn try_read(&self) -> Option<Self::ReadLockType<'_>>;
+
+		/// Turn the exclusive `lock` into a shared one without releasing it,
+		/// no writer can lock the structure in between.
+		fn downgrade<'a>(lock: Self::WriteLockType<'a>) -> Self::ReadLockType<'a> where Self: 'a;
+
+		/// The exclusive lock, provides mutable access to the data.
+		type WriteLockType<'a>: Deref<Target = Self::DerefLockType> + DerefMut where Self: 'a;
+
+		/// The shared lock, provides access to the data for reading.
+		type ReadLockType<'a>: Deref<Target = Self::DerefLockType> where Self: 'a;
+
+		/// The data type provided by the synchronization structure.
+		type DerefLockType;
+	}
+}
+
+/// Sync point whose value is read by several threads (or tasks) at once
+/// (`sync_read!`) and changed by one of them at a time (`sync_write!`).
+///
+/// A writer can turn its lock into a shared one (`downgrade!()`) to keep reading
+/// what it has just written, no other writer gets in between.
+///
+/// Like `SyncPoint`, in debug builds or with the `debug-checks` feature
+/// a thread (or task) locking the point again while holding the exclusive lock
+/// panics instead of waiting forever. Shared locks are not tracked,
+/// so `sync_write!` inside `sync_read!` of the same point is not detected.
+/// The lock order checks (`lockdep`), holders (`track-holders`), `max_hold`
+/// and `stats` (`metrics`) apply only to `SyncPoint`, not to rw points.
+pub struct RwSyncPoint<T>
+where
+	T: RwSyncPointBeh,
+{
+	rwlock: T,
+	/// Name of the sync point for messages.
+	name: Option<&'static str>,
+	/// Holder of the exclusive lock, for the re-entry check.
+	holder: PointHolder,
+}
+
+impl<T> RwSyncPoint<T>
+where
+	T: RwSyncPointBeh,
+{
+	/// Structure creation.
+	#[inline]
+	pub const fn new(rwlock: T) -> Self {
+		Self {
+			rwlock,
+			name: None,
+			holder: PointHolder::new(),
+		}
+	}
+
+	/// Set the name of the sync point used in messages
+	/// (the name of the `sync_point` variable).
+	#[inline]
+	pub const fn with_name(mut self, name: &'static str) -> Self {
+		self.name = Some(name);
+		self
+	}
+
+	/// Name of the sync point used in messages.
+	#[inline]
+	pub const fn name(&self) -> &'static str {
+		match self.name {
+			Some(a) => a,
+			None => "<unnamed>",
+		}
+	}
+
+	cfg_not_async! {
+		/// Create a new exclusive lock.
+		///
+		/// # Panics
+		///
+		/// If the current thread holds the exclusive lock
+		/// (in debug builds or with the `debug-checks` feature).
+		#[track_caller]
+		#[inline]
+		pub fn write(&self) -> RwWriteGuard<'_, T> {
+			let site = LockSite::caller();
+			self.holder.enter(self.name(), site);
+			RwWriteGuard::new(self, T::write(&self.rwlock), site)
+		}
+
+		/// Create a new shared lock.
+		///
+		/// # Panics
+		///
+		/// If the current thread holds the exclusive lock
+		/// (in debug builds or with the `debug-checks` feature).
+		#[track_caller]
+		#[inline]
+		pub fn read(&self) -> RwReadGuard<'_, T> {
+			self.holder.enter(self.name(), LockSite::caller());
+			RwReadGuard {
+				lock: T::read(&self.rwlock),
+			}
+		}
+	}
+
+	cfg_async! {
+		/// Create a new exclusive lock.
+		///
+		/// # Panics
+		///
+		/// If the current task holds the exclusive lock
+		/// (in debug builds or with the `debug-checks` feature).
+		#[track_caller]
+		#[inline]
+		pub fn write(&self) -> impl core::future::Future<Output = RwWriteGuard<'_, T>> {
+			self.write_at(LockSite::caller())
+		}
+
+		/// `write` locking the sync point at `site`.
+		async fn write_at(&self, site: LockSite) -> RwWriteGuard<'_, T> {
+			self.holder.enter(self.name(), site);
+			RwWriteGuard::new(self, T::write(&self.rwlock).await, site)
+		}
+
+		/// Create a new shared lock.
+		///
+		/// # Panics
+		///
+		/// If the current task holds the exclusive lock
+		/// (in debug builds or with the `debug-checks` feature).
+		#[track_caller]
+		#[inline]
+		pub fn read(&self) -> impl core::future::Future<Output = RwReadGuard<'_, T>> {
+			self.read_at(LockSite::caller())
+		}
+
+		/// `read` locking the sync point at `site`.
+		async fn read_at(&self, site: LockSite) -> RwReadGuard<'_, T> {
+			self.holder.enter(self.name(), site);
+			RwReadGuard {
+				lock: T::read(&self.rwlock).await,
+			}
+		}
+	}
+
+	/// If the sync point is locked right now, then return None,
+	/// otherwise create a new exclusive lock.
+	#[track_caller]
+	#[inline]
+	pub fn try_write(&self) -> Option<RwWriteGuard<'_, T>> {
+		Some(RwWriteGuard::new(
+			self,
+			T::try_write(&self.rwlock)?,
+			LockSite::caller(),
+		))
+	}
+
+	/// If the sync point is locked by a writer right now, then return None,
+	/// otherwise create a new shared lock.
+	#[inline]
+	pub fn try_read(&self) -> Option<RwReadGuard<'_, T>> {
+		Some(RwReadGuard {
+			lock: T::try_read(&self.rwlock)?,
+		})
+	}
+}
+
+/// The exclusive lock of `RwSyncPoint`, released on drop.
+pub struct RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	point: &'a RwSyncPoint<T>,
+	lock: ManuallyDrop<T::WriteLockType<'a>>,
+}
+
+impl<'a, T> RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	/// Wrapping the exclusive lock `lock` held on `point`, taken at `site`.
+	#[inline]
+	fn new(point: &'a RwSyncPoint<T>, lock: T::WriteLockType<'a>, site: LockSite) -> Self {
+		point.holder.acquired(site);
+
+		Self {
+			point,
+			lock: ManuallyDrop::new(lock),
+		}
+	}
+
+	/// Turn the lock into a shared one without releasing the sync point,
+	/// other readers are let in, writers are not.
+	#[inline]
+	pub fn downgrade(self) -> RwReadGuard<'a, T> {
+		let mut this = ManuallyDrop::new(self);
+		this.point.holder.released();
+
+		// Safety: the guard is not dropped, the lock is taken only here.
+		let lock = unsafe { ManuallyDrop::take(&mut this.lock) };
+		RwReadGuard {
+			lock: T::downgrade(lock),
+		}
+	}
+}
+
+impl<'a, T> Deref for RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	type Target = T::DerefLockType;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.lock
+	}
+}
+
+impl<'a, T> DerefMut for RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.lock
+	}
+}
+
+impl<'a, T> Drop for RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	fn drop(&mut self) {
+		self.point.holder.released();
+
+		// Safety: the lock is taken only here, the guard is not used after that.
+		unsafe { ManuallyDrop::drop(&mut self.lock) }
+	}
+}
+
+impl<'a, T> fmt::Debug for RwWriteGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+	T::DerefLockType: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("RwWriteGuard").field(&**self).finish()
+	}
+}
+
+/// The shared lock of `RwSyncPoint`, released on drop.
+pub struct RwReadGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	lock: T::ReadLockType<'a>,
+}
+
+impl<'a, T> Deref for RwReadGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+{
+	type Target = T::DerefLockType;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.lock
+	}
+}
+
+impl<'a, T> fmt::Debug for RwReadGuard<'a, T>
+where
+	T: RwSyncPointBeh + 'a,
+	T::DerefLockType: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("RwReadGuard").field(&**self).finish()
+	}
+}
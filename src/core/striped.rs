@@ -0,0 +1,144 @@
+//! Sync points that synchronize code on object instances.
+
+extern crate alloc;
+
+use crate::core::Reentrant;
+use crate::core::SyncPoint;
+use crate::core::SyncPointBeh;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+
+/// Number of stripes of the default table used by `sync!(on obj, ...)`.
+pub const DEFAULT_STRIPES_LEN: usize = 64;
+
+/// A fixed-size table of sync points (stripes),
+/// the object is synchronized by the stripe selected by its address.
+///
+/// Code synchronized on the same object is never executed in parallel,
+/// code synchronized on different objects is usually executed in parallel
+/// (unless their addresses fall into the same stripe).
+///
+/// Stripes are locked through `Reentrant` by `sync!(on obj, ...)`, so nested blocks
+/// of the same thread (or task) on objects of the same stripe do not wait for each other.
+pub struct StripedSyncPoint<T, const N: usize>
+where
+	T: SyncPointBeh,
+{
+	stripes: [SyncPoint<T>; N],
+}
+
+impl<T, const N: usize> StripedSyncPoint<T, N>
+where
+	T: SyncPointBeh,
+{
+	/// Creation of a table from already created stripes.
+	#[inline]
+	pub const fn new(stripes: [SyncPoint<T>; N]) -> Self {
+		assert!(N > 0, "the table of stripes cannot be empty");
+
+		Self { stripes }
+	}
+
+	/// Number of stripes in the table.
+	#[inline]
+	pub const fn stripes_len(&self) -> usize {
+		N
+	}
+
+	/// The sync point (stripe) of the object located at `obj`.
+	#[inline]
+	pub fn stripe_of<O: ?Sized>(&self, obj: &O) -> &SyncPoint<T> {
+		let addr = obj as *const O as *const () as usize as u64;
+		// Fibonacci hashing, the low bits of the address are usually
+		// the same due to alignment.
+		let hash = addr.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32;
+
+		&self.stripes[hash as usize % N]
+	}
+
+	/// Reentrant locking of the stripe of the object located at `obj`.
+	#[inline]
+	pub fn object<O: ?Sized>(&self, obj: &O) -> Reentrant<'_, T> {
+		Reentrant::new(self.stripe_of(obj))
+	}
+}
+
+/// The object synchronized by `sync!(on obj, ...)`, `obj` refers to it.
+///
+/// If `obj` refers to a reference or a smart pointer (`Box`, `Rc`, `Arc`),
+/// the object is the pointed value (`SyncObjectOfPointer`), otherwise the value
+/// itself (`SyncObjectOfValue`), the first applicable method is selected by
+/// `(&SyncObject(obj)).sync_object()` with both traits in scope.
+#[doc(hidden)]
+pub struct SyncObject<'a, O: ?Sized>(pub &'a O);
+
+/// The object of `SyncObject` referring to a reference or a smart pointer.
+#[doc(hidden)]
+pub trait SyncObjectOfPointer {
+	type Object: ?Sized;
+
+	fn sync_object(&self) -> &Self::Object;
+}
+
+impl<O: ?Sized> SyncObjectOfPointer for SyncObject<'_, &O> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
+
+impl<O: ?Sized> SyncObjectOfPointer for SyncObject<'_, &mut O> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
+
+impl<O: ?Sized> SyncObjectOfPointer for SyncObject<'_, Box<O>> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
+
+impl<O: ?Sized> SyncObjectOfPointer for SyncObject<'_, Rc<O>> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
+
+impl<O: ?Sized> SyncObjectOfPointer for SyncObject<'_, Arc<O>> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
+
+/// The object of `SyncObject` referring to any other value.
+#[doc(hidden)]
+pub trait SyncObjectOfValue {
+	type Object: ?Sized;
+
+	fn sync_object(&self) -> &Self::Object;
+}
+
+impl<O: ?Sized> SyncObjectOfValue for &SyncObject<'_, O> {
+	type Object = O;
+
+	#[inline]
+	fn sync_object(&self) -> &O {
+		self.0
+	}
+}
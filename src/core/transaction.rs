@@ -0,0 +1,83 @@
+//! Restoring the value of a sync point if the synchronized code panics.
+
+extern crate std;
+
+use crate::core::SyncGuard;
+use crate::core::SyncPointBeh;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+/// A copy of the value of the sync point taken at the beginning
+/// of a transactional `sync!` block.
+///
+/// If the code of the block panics, the value is restored from the copy
+/// before the sync point is released, so other threads (or tasks)
+/// see either all changes of the block or none of them.
+/// The restored sync point is not poisoned.
+pub struct SyncTransaction<'a, 'b, T>
+where
+	T: SyncPointBeh + 'b,
+	T::DerefLockType: Clone,
+{
+	lock: &'a mut SyncGuard<'b, T>,
+	snapshot: T::DerefLockType,
+	/// The thread was already panicking when the transaction was created.
+	panicking: bool,
+}
+
+impl<'a, 'b, T> SyncTransaction<'a, 'b, T>
+where
+	T: SyncPointBeh + 'b,
+	T::DerefLockType: Clone,
+{
+	/// Taking a copy of the value of the held `lock`.
+	#[inline]
+	pub fn new(lock: &'a mut SyncGuard<'b, T>) -> Self {
+		let snapshot = (**lock).clone();
+
+		Self {
+			lock,
+			snapshot,
+			panicking: std::thread::panicking(),
+		}
+	}
+}
+
+impl<'b, T> Deref for SyncTransaction<'_, 'b, T>
+where
+	T: SyncPointBeh + 'b,
+	T::DerefLockType: Clone,
+{
+	type Target = T::DerefLockType;
+
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		self.lock
+	}
+}
+
+impl<'b, T> DerefMut for SyncTransaction<'_, 'b, T>
+where
+	T: SyncPointBeh + 'b,
+	T::DerefLockType: Clone,
+{
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.lock
+	}
+}
+
+impl<'b, T> Drop for SyncTransaction<'_, 'b, T>
+where
+	T: SyncPointBeh + 'b,
+	T::DerefLockType: Clone,
+{
+	fn drop(&mut self) {
+		// A block running during unwinding (in `Drop`) is rolled back
+		// only if it panics itself.
+		if !self.panicking && std::thread::panicking() {
+			core::mem::swap(&mut **self.lock, &mut self.snapshot);
+			self.lock.restored();
+		}
+	}
+}
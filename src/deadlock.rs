@@ -0,0 +1,254 @@
+//! Detection of deadlocks of sync points using the deadlock detector
+//! of `parking_lot` (the `pl-deadlock-detection` feature).
+//!
+//! `parking_lot` finds the threads waiting for each other, the reports add
+//! the names of the sync points (`sync_point`) these threads hold and wait for
+//! and the places of the `sync!` blocks where they were locked.
+//!
+//! **Only sync points using `parking_lot` are checked.** The `std` feature takes
+//! precedence over `pl`, with `std` (a default feature) or `async` enabled the sync
+//! points use other locks and no deadlock is ever reported, so the default features
+//! must be disabled:
+//!
+//! ```toml
+//! [dependencies.synchronized]
+//! version = "2.0.0"
+//! default-features = false
+//! features = ["pl", "point", "pl-deadlock-detection"]
+//! ```
+//!
+//! ```rust
+//!	use std::time::Duration;
+//!	use synchronized::deadlock;
+//!
+//!	let checker = deadlock::start_checker(Duration::from_secs(10), |report| {
+//!		eprintln!("{}", report);
+//!	});
+//!
+//!	// ...
+//!
+//!	checker.stop();
+//! ```
+
+extern crate alloc;
+extern crate std;
+
+use crate::core::debug::LockSite;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::panic::Location;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread::JoinHandle;
+use std::thread::ThreadId;
+
+/// Sync point and the place of the code where it was locked.
+#[derive(Debug, Clone, Copy)]
+pub struct DeadlockPoint {
+	/// Name of the sync point.
+	pub name: &'static str,
+	/// The place of the code (`sync!`) where the sync point was locked.
+	pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for DeadlockPoint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at {}", self.name, self.location)
+	}
+}
+
+/// Thread participating in a deadlock.
+#[derive(Debug, Clone)]
+pub struct DeadlockedThread {
+	/// Id of the thread.
+	pub thread_id: ThreadId,
+	/// Name of the thread.
+	pub thread_name: Option<String>,
+	/// The sync point the thread is waiting for.
+	pub waiting: Option<DeadlockPoint>,
+	/// The sync points held by the thread in the order of locking.
+	pub held: Vec<DeadlockPoint>,
+}
+
+/// Threads waiting for each other, found by the deadlock detector.
+#[derive(Debug, Clone)]
+pub struct DeadlockReport {
+	/// Threads of the deadlock.
+	pub threads: Vec<DeadlockedThread>,
+}
+
+impl fmt::Display for DeadlockReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "deadlock of {} threads", self.threads.len())?;
+		for thread in &self.threads {
+			match &thread.thread_name {
+				Some(name) => write!(f, "\n\tthread '{}'", name)?,
+				None => write!(f, "\n\tthread {:?}", thread.thread_id)?,
+			}
+			match &thread.waiting {
+				Some(point) => write!(f, " waits for {}", point)?,
+				None => f.write_str(" waits for a lock that is not a sync point")?,
+			}
+			for (i, point) in thread.held.iter().enumerate() {
+				f.write_str(if i == 0 { ", holds " } else { ", " })?;
+				write!(f, "{}", point)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Sync points held and awaited by a thread.
+struct ThreadPoints {
+	name: Option<String>,
+	waiting: Option<(usize, DeadlockPoint)>,
+	held: Vec<(usize, DeadlockPoint)>,
+}
+
+/// Sync points of all threads that hold or wait for them.
+static THREADS: Mutex<Option<HashMap<ThreadId, ThreadPoints>>> = Mutex::new(None);
+
+fn threads() -> MutexGuard<'static, Option<HashMap<ThreadId, ThreadPoints>>> {
+	match THREADS.lock() {
+		Ok(a) => a,
+		Err(e) => e.into_inner(),
+	}
+}
+
+/// Change the sync points of the current thread with `f`,
+/// the thread is forgotten when it no longer holds or waits for sync points.
+fn update_current(f: impl FnOnce(&mut ThreadPoints)) {
+	let thread = std::thread::current();
+	let mut threads = threads();
+	let threads = threads.get_or_insert_with(HashMap::new);
+	let points = threads.entry(thread.id()).or_insert_with(|| ThreadPoints {
+		name: thread.name().map(String::from),
+		waiting: None,
+		held: Vec::new(),
+	});
+	f(points);
+
+	if points.waiting.is_none() && points.held.is_empty() {
+		threads.remove(&thread.id());
+	}
+}
+
+/// The current thread starts waiting for the sync point `point` named `name` at `site`.
+pub(crate) fn waiting(point: usize, name: &'static str, site: LockSite) {
+	let location = site.location();
+	update_current(|a| a.waiting = Some((point, DeadlockPoint { name, location })));
+}
+
+/// The current thread stopped waiting for a sync point without locking it
+/// (the time is up or the waiting was cancelled).
+pub(crate) fn abandoned() {
+	update_current(|a| a.waiting = None);
+}
+
+/// The sync point `point` named `name` was locked at `site` by the current thread.
+pub(crate) fn acquired(point: usize, name: &'static str, site: LockSite) {
+	let location = site.location();
+	update_current(|a| {
+		a.waiting = None;
+		a.held.push((point, DeadlockPoint { name, location }));
+	});
+}
+
+/// The sync point `point` was released by the current thread.
+pub(crate) fn released(point: usize) {
+	update_current(|a| {
+		if let Some(i) = a.held.iter().rposition(|(a, _)| *a == point) {
+			a.held.remove(i);
+		}
+	});
+}
+
+/// Check for deadlocks now, each deadlock is reported only once.
+pub fn check() -> Vec<DeadlockReport> {
+	let deadlocks = parking_lot::deadlock::check_deadlock();
+	if deadlocks.is_empty() {
+		return Vec::new();
+	}
+
+	let threads = threads();
+	deadlocks
+		.iter()
+		.map(|deadlock| DeadlockReport {
+			threads: deadlock
+				.iter()
+				.map(|thread| {
+					let thread_id = thread.thread_id();
+					let points = threads.as_ref().and_then(|a| a.get(&thread_id));
+
+					DeadlockedThread {
+						thread_id,
+						thread_name: points.and_then(|a| a.name.clone()),
+						waiting: points.and_then(|a| a.waiting).map(|(_, a)| a),
+						held: points
+							.map(|a| a.held.iter().map(|(_, a)| *a).collect())
+							.unwrap_or_default(),
+					}
+				})
+				.collect(),
+		})
+		.collect()
+}
+
+/// Background thread checking for deadlocks (`start_checker`).
+///
+/// Dropping the checker leaves the thread running, `stop` stops it.
+#[derive(Debug)]
+pub struct DeadlockChecker {
+	stopped: Arc<AtomicBool>,
+	thread: JoinHandle<()>,
+}
+
+impl DeadlockChecker {
+	/// Stop checking and wait for the thread to finish.
+	pub fn stop(self) {
+		self.stopped.store(true, Ordering::Release);
+		self.thread.thread().unpark();
+
+		let _e = self.thread.join();
+	}
+}
+
+/// Start a background thread checking for deadlocks every `interval`,
+/// `callback` is called once for each deadlock found.
+///
+/// Deadlocks of sync points are found only if they use `parking_lot`,
+/// that is the `std` and `async` features are disabled (see the module documentation).
+pub fn start_checker(
+	interval: Duration,
+	callback: impl Fn(&DeadlockReport) + Send + 'static,
+) -> DeadlockChecker {
+	let stopped = Arc::new(AtomicBool::new(false));
+	let thread = {
+		let stopped = stopped.clone();
+
+		std::thread::Builder::new()
+			.name(String::from("synchronized-deadlock-checker"))
+			.spawn(move || {
+				loop {
+					std::thread::park_timeout(interval);
+					if stopped.load(Ordering::Acquire) {
+						break;
+					}
+
+					for report in check() {
+						callback(&report);
+					}
+				}
+			})
+			.expect("failed to spawn the deadlock checker thread")
+	};
+
+	DeadlockChecker { stopped, thread }
+}
@@ -220,11 +220,16 @@ Synchronized supports locks from the standard `std` package, as well as the `par
 
 ```rust,ignore
 [dependencies.synchronized]
-version = "1.1.0"
+version = "2.0.0"
 default-features = false
 features = [
 	"std",
 	#"point", # Allows the use of synchronization points to avoid executing code in two or more places at the same time.
+	#"attr", # Allows the use of the `#[synchronized]` attribute to synchronize whole functions.
+	#"debug-checks", # Detects a thread (or task) re-entering a sync point it holds also in release builds.
+	#"lockdep", # Detects an inconsistent order of locking named sync points (a possible deadlock).
+	#"track-holders", # Tracks who holds each sync point, since when and from where (`dump_holders`).
+	#"metrics", # Counts acquisitions, contention, waiting and holding of each sync point (`SyncPoint::stats`).
 ]
 ```
 
@@ -232,11 +237,17 @@ features = [
 
 ```rust,ignore
 [dependencies.synchronized]
-version = "1.1.0"
+version = "2.0.0"
 default-features = false
 features = [
 	"pl",
 	#"point", # Allows the use of synchronization points to avoid executing code in two or more places at the same time.
+	#"attr", # Allows the use of the `#[synchronized]` attribute to synchronize whole functions.
+	#"debug-checks", # Detects a thread (or task) re-entering a sync point it holds also in release builds.
+	#"lockdep", # Detects an inconsistent order of locking named sync points (a possible deadlock).
+	#"track-holders", # Tracks who holds each sync point, since when and from where (`dump_holders`).
+	#"metrics", # Counts acquisitions, contention, waiting and holding of each sync point (`SyncPoint::stats`).
+	#"pl-deadlock-detection", # Reports deadlocks with the names of the sync points (`deadlock::start_checker`), not with `std` or `async`.
 ]
 ```
 
@@ -244,11 +255,16 @@ features = [
 
 ```rust,ignore
 [dependencies.synchronized]
-version = "1.1.0"
+version = "2.0.0"
 default-features = false
 features = [
 	"async",
 	#"point", # Allows the use of synchronization points to avoid executing code in two or more places at the same time.
+	#"attr", # Allows the use of the `#[synchronized]` attribute to synchronize whole functions.
+	#"debug-checks", # Detects a thread (or task) re-entering a sync point it holds also in release builds.
+	#"lockdep", # Detects an inconsistent order of locking named sync points (a possible deadlock).
+	#"track-holders", # Tracks who holds each sync point, since when and from where (`dump_holders`).
+	#"metrics", # Counts acquisitions, contention, waiting and holding of each sync point (`SyncPoint::stats`).
 ]
 ```
 */
@@ -266,6 +282,170 @@ mod point;
 pub mod beh;
 pub(crate) mod cfg;
 
+#[cfg(feature = "attr")]
+#[doc(hidden)]
+pub mod attr;
+
+#[cfg(feature = "pl-deadlock-detection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pl-deadlock-detection")))]
+pub mod deadlock;
+
+#[cfg(feature = "track-holders")]
+#[cfg_attr(docsrs, doc(cfg(feature = "track-holders")))]
+pub use crate::core::dump_holders;
+
+/// Attribute that turns the whole function (or all methods of the `impl` block)
+/// into a synchronized block.
+///
+/// ### 1. The function is synchronized on its own anonymous sync point.
+/// ```rust
+///	use synchronized::synchronized;
+///
+///	#[synchronized]
+///	fn parse_and_store(value: &str) -> Result<u32, core::num::ParseIntError> {
+///		static mut LAST: u32 = 0;
+///
+///		// `?` and `return` release the lock as well.
+///		let value = value.parse()?;
+///		unsafe {
+///			LAST = value;
+///		}
+///
+///		Ok(value)
+///	}
+///
+///	assert_eq!(parse_and_store("10"), Ok(10));
+///	assert!(parse_and_store("-").is_err());
+/// ```
+///
+/// ### 2. Several functions are synchronized on one named sync point.
+/// ```rust
+///	use synchronized::sync_point;
+///	use synchronized::synchronized;
+///
+///	sync_point!(DB_SYNC);
+///
+///	#[synchronized(DB_SYNC)]
+///	fn read() -> usize {
+///		0
+///	}
+///
+///	#[synchronized(DB_SYNC)]
+///	fn write(_value: usize) {}
+///
+///	write(read());
+/// ```
+///
+/// ### 3. All methods of the type share one sync point (a monitor).
+///
+/// The sync point belongs to the type: all its `#[synchronized]` `impl` blocks
+/// share it, and each set of type arguments of a generic type has its own
+/// (the type arguments must be `'static`).
+/// Synchronized methods can call each other, the locks of the attribute are reentrant.
+/// ```rust
+///	use synchronized::synchronized;
+///
+///	struct Counter;
+///
+///	static mut COUNT: usize = 0;
+///
+///	#[synchronized]
+///	impl Counter {
+///		fn inc(&self) -> usize {
+///			unsafe {
+///				COUNT += 1;
+///				COUNT
+///			}
+///		}
+///
+///		fn get(&self) -> usize {
+///			unsafe { COUNT }
+///		}
+///
+///		// Calls synchronized methods without waiting for itself.
+///		fn inc_twice(&self) -> usize {
+///			self.inc();
+///			self.inc()
+///		}
+///
+///		// Excluded from synchronization.
+///		#[synchronized(skip)]
+///		fn name(&self) -> &'static str {
+///			"counter"
+///		}
+///	}
+///
+///	// Shares the sync point with the block above.
+///	#[synchronized]
+///	impl Counter {
+///		fn reset(&self) {
+///			unsafe { COUNT = 0 }
+///		}
+///	}
+///
+///	assert_eq!(Counter.inc(), 1);
+///	assert_eq!(Counter.inc_twice(), 3);
+///	assert_eq!(Counter.get(), 3);
+///	assert_eq!(Counter.name(), "counter");
+///	Counter.reset();
+///	assert_eq!(Counter.get(), 0);
+/// ```
+///
+/// ### 4. Methods are synchronized on the object `&self` (`sync!(on self, ...)`).
+/// ```rust
+///	use std::sync::atomic::AtomicUsize;
+///	use std::sync::atomic::Ordering;
+///	use synchronized::synchronized;
+///
+///	struct Account {
+///		balance: AtomicUsize,
+///	}
+///
+///	#[synchronized(instance)]
+///	impl Account {
+///		// Functions without `&self` are not synchronized.
+///		fn new(balance: usize) -> Self {
+///			Self { balance: AtomicUsize::new(balance) }
+///		}
+///
+///		fn withdraw(&self, amount: usize) -> bool {
+///			let balance = self.balance.load(Ordering::Relaxed);
+///			if balance < amount {
+///				return false;
+///			}
+///			self.balance.store(balance - amount, Ordering::Relaxed);
+///
+///			true
+///		}
+///	}
+///
+///	let account = Account::new(10);
+///	assert!(account.withdraw(7));
+///	assert!(!account.withdraw(7));
+/// ```
+///
+/// ### 5. The library is used under another path (`crate = path`).
+/// ```rust
+///	mod reexport {
+///		pub use synchronized as sync_lib;
+///	}
+///
+///	#[reexport::sync_lib::synchronized(crate = reexport::sync_lib)]
+///	fn next_id() -> usize {
+///		static mut ID: usize = 0;
+///
+///		unsafe {
+///			ID += 1;
+///			ID
+///		}
+///	}
+///
+///	assert_eq!(next_id(), 1);
+/// ```
+#[cfg(feature = "attr")]
+#[cfg_attr(docsrs, doc(cfg(feature = "attr")))]
+pub use synchronized_attr::synchronized;
+
 /// Convenient and simple macro for code synchronization in multithreading.
 ///
 /// ### 1. Anonymous code is synchronized in multi-threaded mode.
@@ -297,6 +477,197 @@ pub(crate) mod cfg;
 ///		sync_let.clone()
 ///	});
 /// ```
+///
+/// ### 3. Anonymous synchronized code with a value created on first lock.
+///
+/// Values marked as `lazy` can be created by non-const expressions.
+/// ```rust
+///	use std::collections::HashMap;
+///	use synchronized::sync;
+///
+///	let result = sync!((cache: HashMap<usize, String> = lazy HashMap::new()) {
+///		cache.entry(1).or_insert_with(|| "1".to_string()).clone()
+///	});
+///	assert_eq!(result, "1");
+/// ```
+///
+/// ### 4. Synchronized code in generic functions.
+///
+/// By default, anonymous synchronized code in a generic function is shared by all
+/// its instantiations. If the generic parameters are listed before the block,
+/// a separate synchronization point is created for each set of types on first use
+/// (the types must be `'static`), this also allows the values to depend on them.
+/// ```rust
+///	use synchronized::sync;
+///
+///	fn push<T: Send + 'static>(value: T) -> usize {
+///		sync!(<T> (buf: Vec<T> = Vec::new()) {
+///			buf.push(value);
+///
+///			buf.len()
+///		})
+///	}
+///
+///	assert_eq!(push(1u8), 1);
+///	assert_eq!(push(2u8), 2);
+///	assert_eq!(push("test"), 1);
+/// ```
+///
+/// ### 5. Waiting for the sync point with a time limit.
+///
+/// With `timeout = Duration` the macro returns `Result` with the result of the code,
+/// or `TimedOut` if the sync point was not released in time (in the asynchronous
+/// version, the `tokio` runtime must have the time driver enabled).
+/// ```rust
+///	use std::time::Duration;
+///	use synchronized::sync;
+///
+///	let result = sync!(timeout = Duration::from_millis(50), (count: usize = 0) {
+///		*count += 1;
+///
+///		*count
+///	});
+///	assert_eq!(result, Ok(1));
+/// ```
+///
+/// ### 6. Cancellable waiting for the sync point.
+///
+/// With `cancel = &CancelToken` the macro returns `Result` with the result of the code,
+/// or `Cancelled` if the token was cancelled (by another thread) before the sync point
+/// was released.
+///
+/// An asynchronous task waits for the release and the cancellation at once.
+/// A blocked thread cannot wait for both, it waits for the release in slices
+/// of 1ms and checks the token between them: the cancellation is noticed
+/// up to 1ms later, and the waiting thread wakes up about a thousand times
+/// a second, use it for waits that are expected to be short or rare.
+/// ```rust
+///	use synchronized::core::CancelToken;
+///	use synchronized::core::Cancelled;
+///	use synchronized::sync;
+///
+///	let token = CancelToken::new();
+///	let result = sync!(cancel = &token, (count: usize = 0) {
+///		*count += 1;
+///
+///		*count
+///	});
+///	assert_eq!(result, Ok(1));
+///
+///	// For example, during shutdown.
+///	token.cancel();
+///	let result = sync!(cancel = &token, (count: usize = 0) {
+///		*count += 1;
+///	});
+///	assert_eq!(result, Err(Cancelled));
+/// ```
+///
+/// ### 7. Synchronized code by several named sync points at once.
+///
+/// All listed points are locked in a globally consistent order (by their address)
+/// and released in the reverse order, so blocks listing the same points in a
+/// different order cannot deadlock each other.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(ACCOUNTS: Vec<usize> = Vec::new(); LEDGER: usize = 0);
+///
+///	sync!((->ACCOUNTS(accounts), ->LEDGER(ledger)) {
+///		accounts.push(10);
+///		*ledger += 10;
+///	});
+///
+///	let total = sync!((->LEDGER(ledger), ->ACCOUNTS(accounts)) {
+///		assert_eq!(accounts.iter().sum::<usize>(), *ledger);
+///
+///		*ledger
+///	});
+///	assert_eq!(total, 10);
+/// ```
+///
+/// ### 8. Synchronized code for each key value.
+///
+/// With `key = value` only one thread at a time executes the code for the same key,
+/// code for different keys is executed in parallel.
+/// ```rust
+///	use synchronized::sync;
+///
+///	fn update_user(user_id: u64) {
+///		sync!(key = user_id, {
+///			// Only one thread at a time for this `user_id`.
+///		});
+///	}
+///
+///	update_user(1);
+///	update_user(2);
+/// ```
+///
+/// ### 9. Synchronized code on an object instance.
+///
+/// With `on obj` only one thread at a time executes the code for the same object,
+/// the object is identified by its address in the table of lock stripes.
+/// `obj` is a reference to the object, if it refers to a reference or a smart pointer
+/// (`Box`, `Rc`, `Arc`), the object is the value they point to (`on &self` and `on self`
+/// are the same object). Nested `on` blocks of one thread (or task) do not wait
+/// for each other, even if their objects fall into the same stripe.
+/// ```rust
+///	use synchronized::sync;
+///
+///	use std::sync::atomic::AtomicU64;
+///	use std::sync::atomic::Ordering;
+///
+///	struct Account {
+///		balance: AtomicU64,
+///	}
+///
+///	impl Account {
+///		fn withdraw(&self, amount: u64) -> bool {
+///			sync!(on &self, {
+///				// Only one thread at a time checks and changes this account.
+///				let balance = self.balance.load(Ordering::Relaxed);
+///				if balance < amount {
+///					return false;
+///				}
+///				self.balance.store(balance - amount, Ordering::Relaxed);
+///
+///				true
+///			})
+///		}
+///	}
+///
+///	let account = Account { balance: AtomicU64::new(10) };
+///	assert!(account.withdraw(7));
+///	assert!(!account.withdraw(7));
+/// ```
+///
+/// ### 10. Synchronized code whose changes are rolled back on panic.
+///
+/// With `transactional` the values (which must implement `Clone`) are copied
+/// at the beginning of the block and restored if the code panics.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(COMB_SYNC: (String, usize) = (String::new(), 0));
+///
+///	let result = std::panic::catch_unwind(|| {
+///		sync!(transactional ->COMB_SYNC(sync_let, count) {
+///			sync_let.push_str("1 ");
+///			if sync_let.len() > 1 {
+///				panic!("the count was not updated");
+///			}
+///			*count += 1;
+///		});
+///	});
+///	assert!(result.is_err());
+///
+///	// The half-made changes are not visible.
+///	sync!(->COMB_SYNC(sync_let, count) {
+///		assert_eq!(sync_let, "");
+///		assert_eq!(*count, 0);
+///	});
+/// ```
 #[macro_export]
 macro_rules! sync {
 	{
@@ -304,9 +675,9 @@ macro_rules! sync {
 		// of synchronized name `$v_point_name`, type `$ty` and value when
 		// `$expr` is created.
 		// (Use only with `sync_point`.)
-		->$sync_point_name:ident ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
+		@[lock] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
 	} => {{ // synchronized point
-		$crate::__sync_beh!(#new_lock(__lock): $sync_point_name);
+		$crate::__sync_beh!(#new_lock(__lock): $($sync_point_name)::+);
 
 		let ( $(ref mut $v_point_name),* ) = *__lock;
 		let result = {
@@ -316,20 +687,200 @@ macro_rules! sync {
 			drop($v_point_name);
 		)*
 
-		$crate::__sync_beh!(#drop_lock(__lock): $sync_point_name);
+		$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+		result
+	}};
+
+	{
+		// The same as `@[lock]`, but the code is executed only
+		// if the sync point is not locked right now, without waiting.
+		@[try] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
+	} => {{ // try synchronized point
+		match $($sync_point_name)::+.try_lock() {
+			Some(mut __lock) => {
+				let ( $(ref mut $v_point_name),* ) = *__lock;
+				let result = {
+					$($all)*
+				};
+				$(
+					drop($v_point_name);
+				)*
+
+				$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+				Some(result)
+			},
+			None => None,
+		}
+	}};
+
+	{
+		// The same as `@[lock]`, but waiting for the sync point
+		// to be released for no longer than `$timeout`.
+		@[timeout($timeout:expr)] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
+	} => {{ // timed synchronized point
+		$crate::__sync_beh!(#try_lock_for(__lock, $timeout): $($sync_point_name)::+);
+
+		match __lock {
+			Some(mut __lock) => {
+				let ( $(ref mut $v_point_name),* ) = *__lock;
+				let result = {
+					$($all)*
+				};
+				$(
+					drop($v_point_name);
+				)*
+
+				$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+				Ok(result)
+			},
+			None => Err($crate::core::TimedOut),
+		}
+	}};
+
+	{
+		// The same as `@[lock]`, but the values are restored
+		// if the code panics (`SyncTransaction`).
+		@[transactional] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
+	} => {{ // transactional synchronized point
+		$crate::__sync_beh!(#new_lock(__lock): $($sync_point_name)::+);
+
+		let mut __transaction = $crate::core::SyncTransaction::new(&mut __lock);
+		let ( $(ref mut $v_point_name),* ) = *__transaction;
+		let result = {
+			$($all)*
+		};
+		$(
+			drop($v_point_name);
+		)*
+		drop(__transaction);
+
+		$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+		result
+	}};
+
+	{
+		// The same as `@[lock]`, but waiting for the sync point
+		// is abandoned if `$token` is cancelled.
+		@[cancel($token:expr)] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) $($all:tt)*
+	} => {{ // cancellable synchronized point
+		$crate::__sync_beh!(#lock_cancellable(__lock, $token): $($sync_point_name)::+);
+
+		match __lock {
+			Ok(mut __lock) => {
+				let ( $(ref mut $v_point_name),* ) = *__lock;
+				let result = {
+					$($all)*
+				};
+				$(
+					drop($v_point_name);
+				)*
+
+				$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+				Ok(result)
+			},
+			Err(e) => Err(e),
+		}
+	}};
+
+	{
+		// Skipping the next sync point without mutable values in `$locks` (`@[lock] (...)`).
+		@[bind_all] ($($locks:tt)*) [$($all:tt)*] ->$($sync_point_name:ident)::+ ( ) $(, $($unk:tt)*)?
+	} => {{
+		let (_, ref mut __locks) = $($locks)*;
+
+		$crate::sync! {
+			@[bind_all] (*__locks) [$($all)*] $($($unk)*)?
+		}
+	}};
+	{
+		// Binding of mutable values of the next sync point from `$locks` (`@[lock] (...)`).
+		@[bind_all] ($($locks:tt)*) [$($all:tt)*] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),+ ) $(, $($unk:tt)*)?
+	} => {{
+		let (ref mut __lock, ref mut __locks) = $($locks)*;
+		let ( $(ref mut $v_point_name),+ ) = **__lock;
+
+		$crate::sync! {
+			@[bind_all] (*__locks) [$($all)*] $($($unk)*)?
+		}
+	}};
+	{
+		// Binding of mutable values of the sync point is completed.
+		@[bind_all] ($($locks:tt)*) [$($all:tt)*]
+	} => {{
+		$($all)*
+	}};
+
+	{
+		// Synchronized block on the object `$obj` using the table of stripes
+		// `$sync_point_name`. (Use only with `sync_point`.)
+		@[on] ($obj:expr) ->$($sync_point_name:ident)::+ $body:block
+	} => {{ // synchronized point on object
+		let __point = $crate::sync!(@[object] ($obj) $($sync_point_name)::+);
+		$crate::__sync_beh!(#new_lock(__lock): __point);
+
+		let result = $body;
+
+		$crate::__sync_beh!(#drop_lock(__lock): __point);
 
 		result
 	}};
 
+	{
+		// Synchronized block on the object `$obj` using the default table of stripes.
+		@[on] ($obj:expr) $body:block
+	} => {{ // synchronized block on object
+		let __point = $crate::sync!(@[object] ($obj) $crate::__OBJECT_SYNC_POINT);
+		$crate::__sync_beh!(#new_lock(__lock): __point);
+
+		let result = $body;
+
+		$crate::__sync_beh!(#drop_lock(__lock): __point);
+
+		result
+	}};
+
+	{
+		// Reentrant locking of the stripe of the object `$obj` (a reference to the object,
+		// or to a reference or a smart pointer pointing to it) in the table `$points`.
+		@[object] ($obj:expr) $points:path
+	} => {{
+		#[allow(unused_imports)]
+		use $crate::core::SyncObjectOfPointer as _;
+		#[allow(unused_imports)]
+		use $crate::core::SyncObjectOfValue as _;
+
+		$crate::core::StripedSyncPoint::object(
+			&$points,
+			(&$crate::core::SyncObject($obj)).sync_object(),
+		)
+	}};
+
+	{
+		// Named sync block $sync_point_name with mutable values written
+		// comma-separated sync name $v_point_name, type $ty and value
+		// created by $expr on first lock.
+		@$mode:tt $sync_point_name:ident ( $($v_point_name: ident: $ty: ty = lazy $expr:expr),* $(,)? ) $($all:tt)*
+	} => {{
+		$crate::__sync_beh!(#new_lazy_point<($($ty),*): [($($expr),*)]>: pub $sync_point_name);
+		$crate::sync! {
+			@$mode ->$sync_point_name ( $($v_point_name),* ) $($all)*
+		}
+	}};
+
 	{
 		// Named `$sync_point_name` synchronized block with mutable value
 		// of synchronized name `$v_point_name`, type `$ty` and value when
 		// `$expr` is created.
-		$sync_point_name:ident ( $v_point_name: ident: $ty: ty = $expr:expr $(,)? ) $($all:tt)*
+		@$mode:tt $sync_point_name:ident ( $v_point_name: ident: $ty: ty = $expr:expr $(,)? ) $($all:tt)*
 	} => {{
-		$crate::__sync_beh!(#new_point<$ty: [$expr]>: $sync_point_name);
+		$crate::__sync_beh!(#new_point<$ty: [$expr]>: pub $sync_point_name);
 		$crate::sync! {
-			->$sync_point_name ($v_point_name) $($all)*
+			@$mode ->$sync_point_name ($v_point_name) $($all)*
 		}
 	}};
 
@@ -337,49 +888,115 @@ macro_rules! sync {
 		// Named sync block $sync_point_name with mutable values written
 		// comma-separated sync name $v_point_name, type $ty and value when
 		// $expr was created.
-		$sync_point_name:ident ( $($v_point_name: ident: $ty: ty = $expr:expr),* $(,)? ) $($all:tt)*
+		@$mode:tt $sync_point_name:ident ( $($v_point_name: ident: $ty: ty = $expr:expr),* $(,)? ) $($all:tt)*
 	} => {{
-		$crate::__sync_beh!(#new_point<($($ty),*): [($($expr),*)]>: $sync_point_name);
+		$crate::__sync_beh!(#new_point<($($ty),*): [($($expr),*)]>: pub $sync_point_name);
 		$crate::sync! {
-			->$sync_point_name ( $($v_point_name),* ) $($all)*
+			@$mode ->$sync_point_name ( $($v_point_name),* ) $($all)*
 		}
 	}};
 
 	{
 		// Named sync block named `$v_point_name`.
 		// (Use only with `sync_point`.)
-		(->$v_point_name: ident) $($all:tt)*
+		@$mode:tt (->$($v_point_name: ident)::+) $($all:tt)*
+	} => {{ // sync point
+		$crate::sync! {
+			@$mode ->$($v_point_name)::+ (__empty_value) $($all)*
+		}
+	}};
+
+	{
+		// Named sync block named `$sync_point_name` with mutable value
+		// of synchronized name `$v_point_name`.
+		// (Use only with `sync_point`.)
+		@$mode:tt (->$($sync_point_name: ident)::+ ( $($v_point_name: ident),* $(,)? )) $($all:tt)*
 	} => {{ // sync point
 		$crate::sync! {
-			->$v_point_name (__empty_value) $($all)*
+			@$mode ->$($sync_point_name)::+ ( $($v_point_name),* ) $($all)*
 		}
 	}};
 
+	{
+		// Synchronized block by several named sync points at once with mutable
+		// values of synchronized names `$v_point_name` of each of them.
+		// The points are locked in a globally consistent order (by address).
+		// (Use only with `sync_point`.)
+		@[lock] ( $( ->$($sync_point_name:ident)::+ $(( $($v_point_name: ident),* $(,)? ))? ),+ $(,)? ) $($all:tt)*
+	} => {{ // synchronized points
+		let __points = ( $(&$($sync_point_name)::+,)+ );
+		$crate::__sync_beh!(#new_lock_all(__locks): __points);
+
+		let result = $crate::sync! {
+			@[bind_all] (__locks) [$($all)*] $( ->$($sync_point_name)::+ ( $($($v_point_name),*)? ) ),+
+		};
+
+		$crate::core::SyncPointSet::unlock_all(__points, __locks);
+
+		result
+	}};
+
 	{
 		// Named sync block named `$v_point_name`.
-		($v_point_name: ident) $($all:tt)*
+		@$mode:tt ($v_point_name: ident) $($all:tt)*
 	} => {{
 		$crate::sync! {
-			$v_point_name (__empty_value: () = ()) $($all)*
+			@$mode $v_point_name (__empty_value: () = ()) $($all)*
+		}
+	}};
+	{
+		// Anonymous synchronized block with mutable synchronized name value
+		// `$v_point_name`, type `$ty` and value created by `$expr` on first lock.
+		@$mode:tt ( $($v_point_name: ident: $ty: ty = lazy $expr:expr),* $(,)? ) $($all:tt)*
+	} => {{ // lazy sync value
+		$crate::sync! {
+			@$mode __ANONYMOUS_SYNC_POINT ( $($v_point_name: $ty = lazy $expr),* ) $($all)*
 		}
 	}};
 	{
 		// Anonymous synchronized block with mutable synchronized name value
 		// `$v_point_name`, type `$ty` and value when `$expr` is created.
-		( $($v_point_name: ident: $ty: ty = $expr:expr),* $(,)? ) $($all:tt)*
+		@$mode:tt ( $($v_point_name: ident: $ty: ty = $expr:expr),* $(,)? ) $($all:tt)*
 	} => {{ // sync value
 		$crate::sync! {
-			__ANONYMOUS_SYNC_POINT ( $($v_point_name: $ty = $expr),* ) $($all)*
+			@$mode __ANONYMOUS_SYNC_POINT ( $($v_point_name: $ty = $expr),* ) $($all)*
+		}
+	}};
+
+	{
+		// Anonymous synchronized block with one synchronization point
+		// for each set of types `$gen` (for use in generic functions)
+		// with mutable synchronized name value `$v_point_name`,
+		// type `$ty` and value when `$expr` is created.
+		@$mode:tt < $($gen:ty),+ $(,)? > ( $($v_point_name: ident: $ty: ty = $expr:expr),* $(,)? ) $($all:tt)*
+	} => {{ // typed sync value
+		struct __SyncSite;
+
+		$crate::__sync_beh!(
+			#new_typed_point<($($ty),*): [($($expr),*)]>: (__SyncSite, $($gen),+) => __ANONYMOUS_SYNC_POINT
+		);
+		$crate::sync! {
+			@$mode ->__ANONYMOUS_SYNC_POINT ( $($v_point_name),* ) $($all)*
+		}
+	}};
+
+	{
+		// Anonymous synchronized block with one synchronization point
+		// for each set of types `$gen` (for use in generic functions).
+		@$mode:tt < $($gen:ty),+ $(,)? > $($all:tt)*
+	} => {{ // typed nohead synchronized block
+		$crate::sync! {
+			@$mode < $($gen),+ > (__empty_value: () = ()) $($all)*
 		}
 	}};
 
 	{
 		// COMPILE_ERROR
-		$(->$_ident1:ident)? /* OR */ $($_ident2:ident)? ($($unk_in:tt)*) $($unk:tt)+
+		@$mode:tt $(->$($_ident1:ident)::+)? /* OR */ $($_ident2:ident)? ($($unk_in:tt)*) $($unk:tt)+
 	} => {
 		compile_error!(concat!(
 			"Error writing macro `synchronized`, incode: ",
-			$(stringify!(->$_ident1),)?
+			$(stringify!(->$($_ident1)::+),)?
 			$(stringify!($_ident2),)?
 
 			stringify!(($($unk_in)*)),
@@ -390,17 +1007,497 @@ macro_rules! sync {
 
 	{
 		// Anonymous synchronized block
-		$($all:tt)*
+		@$mode:tt $($all:tt)*
 	} => {{ // nohead synchronized block
 		$crate::sync! {
-			(__empty_value: () = ()) $($all)*
+			@$mode (__empty_value: () = ()) $($all)*
 		}
 	}};
 
+	{
+		// Synchronized block by the key `$key` of the named keyed sync point
+		// `$sync_point_name`, only one block at a time is executed for the same key.
+		// (Use only with `sync_point`.)
+		key = $key:expr, ->$($sync_point_name:ident)::+ $body:block
+	} => {{ // keyed synchronized point
+		let __entry = $crate::core::KeyedSyncPoint::entry(&$($sync_point_name)::+, $key);
+		$crate::__sync_beh!(#new_lock(__lock): __entry);
+
+		let result = $body;
+
+		$crate::__sync_beh!(#drop_lock(__lock): __entry);
+		drop(__entry);
+
+		result
+	}};
+
+	{
+		// Anonymous synchronized block by the key `$key`,
+		// only one block at a time is executed for the same key.
+		key = $key:expr, $body:block
+	} => {{ // keyed synchronized block
+		$crate::__sync_beh!(#new_keyed_point<u64>: __ANONYMOUS_KEYED_SYNC_POINT);
+
+		$crate::sync! {
+			key = $crate::core::key_hash(&$key), ->__ANONYMOUS_KEYED_SYNC_POINT $body
+		}
+	}};
+
+	{
+		// Synchronized block on the object `$obj`.
+		on $obj:expr, $($all:tt)*
+	} => {
+		$crate::sync! {
+			@[on] ($obj) $($all)*
+		}
+	};
+
+	{
+		// Synchronized block whose values are restored if the code panics,
+		// other threads see either all changes of the block or none of them.
+		transactional $($all:tt)*
+	} => {
+		$crate::sync! {
+			@[transactional] $($all)*
+		}
+	};
+
+	{
+		// Synchronized block waiting for the sync point to be released
+		// for no longer than `$timeout`.
+		timeout = $timeout:expr, $($all:tt)*
+	} => {
+		$crate::sync! {
+			@[timeout($timeout)] $($all)*
+		}
+	};
+
+	{
+		// Synchronized block waiting for the sync point to be released
+		// until `$token` is cancelled.
+		cancel = $token:expr, $($all:tt)*
+	} => {
+		$crate::sync! {
+			@[cancel($token)] $($all)*
+		}
+	};
+
+	{
+		// Synchronized block waiting for the sync point to be released.
+		$($all:tt)*
+	} => {
+		$crate::sync! {
+			@[lock] $($all)*
+		}
+	};
+
 	[] => {}
 }
 
+/// Synchronized code that is executed only if its sync point is not locked right now.
+///
+/// Accepts the same forms as the [`sync`] macro and returns `Option` with the result
+/// of the code, `None` means that the code was skipped because the sync point is
+/// held by someone else. It never waits (in the asynchronous version as well).
+///
+/// ### 1. Anonymous synchronized code with one mutable variable.
+/// ```rust
+///	use synchronized::try_sync;
+///
+///	let result = try_sync!((count: usize = 0) {
+///		*count += 1;
+///
+///		*count
+///	});
+///	assert_eq!(result, Some(1));
+/// ```
+///
+/// ### 2. Synchronized code by a named sync point.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///	use synchronized::try_sync;
+///
+///	sync_point!(COMB_SYNC: usize = 0);
+///
+///	sync!(->COMB_SYNC(count) {
+///		// The sync point is held here, so the code is skipped.
+///		let result = try_sync!((->COMB_SYNC(count)) {
+///			*count += 1;
+///		});
+///		assert_eq!(result, None);
+///
+///		*count += 1;
+///	});
+///
+///	let result = try_sync!((->COMB_SYNC(count)) {
+///		*count
+///	});
+///	assert_eq!(result, Some(1));
+/// ```
+#[macro_export]
+macro_rules! try_sync {
+	[ $($all:tt)* ] => {
+		$crate::sync! {
+			@[try] $($all)*
+		}
+	};
+}
+
+/// Synchronized code that is executed only after the condition on the data
+/// of the named sync point becomes true.
+///
+/// While the condition is false, the sync point is released and the code waits
+/// for a notification (`sync_notify!`), then the condition is checked again
+/// with the sync point held.
+///
+/// ### 1. Producer and consumer.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_notify;
+///	use synchronized::sync_point;
+///	use synchronized::sync_wait;
+///
+///	sync_point!(QUEUE_SYNC: Vec<usize> = Vec::new());
+///
+///	let consumer = std::thread::spawn(|| {
+///		sync_wait!(->QUEUE_SYNC(queue) until !queue.is_empty() {
+///			queue.pop()
+///		})
+///	});
+///
+///	sync!(->QUEUE_SYNC(queue) {
+///		queue.push(1);
+///	});
+///	sync_notify!(QUEUE_SYNC, one);
+///
+///	assert_eq!(consumer.join().unwrap(), Some(1));
+/// ```
+#[macro_export]
+macro_rules! sync_wait {
+	{
+		// Named `$sync_point_name` synchronized block with mutable value
+		// of synchronized name `$v_point_name`, the code is executed
+		// only after the condition becomes true.
+		// (Use only with `sync_point`.)
+		->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) until $($all:tt)+
+	} => {
+		$crate::sync_wait! {
+			@[until] ->$($sync_point_name)::+ ( $($v_point_name),* ) [] $($all)+
+		}
+	};
+
+	{
+		// The condition is collected, only the code block remains.
+		@[until] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($condition:tt)+] { $($all:tt)* }
+	} => {{ // synchronized point with condition
+		$crate::__sync_beh!(#new_lock(__lock): $($sync_point_name)::+);
+		$crate::__sync_beh!(#wait_while(__lock, |__value| {
+			let ( $(ref mut $v_point_name),* ) = *__value;
+
+			!($($condition)+)
+		}): $($sync_point_name)::+);
+
+		let ( $(ref mut $v_point_name),* ) = *__lock;
+		let result = {
+			$($all)*
+		};
+		$(
+			drop($v_point_name);
+		)*
+
+		$crate::__sync_beh!(#drop_lock(__lock): $($sync_point_name)::+);
+
+		result
+	}};
+
+	{
+		// Collecting the condition token by token up to the code block.
+		@[until] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($condition:tt)*] $next:tt $($all:tt)+
+	} => {
+		$crate::sync_wait! {
+			@[until] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($condition)* $next] $($all)+
+		}
+	};
+}
+
+/// Wake up the code waiting in `sync_wait!` for the condition
+/// on the data of the named sync point.
+///
+/// `one` wakes up one waiting thread (or task), `all` wakes up all of them.
+///
+/// ```rust
+///	use synchronized::sync_notify;
+///	use synchronized::sync_point;
+///
+///	sync_point!(QUEUE_SYNC: Vec<usize> = Vec::new());
+///
+///	// Nobody is waiting, the notifications are just skipped.
+///	sync_notify!(QUEUE_SYNC, one);
+///	sync_notify!(QUEUE_SYNC, all);
+/// ```
+#[macro_export]
+macro_rules! sync_notify {
+	[ $($sync_point_name:ident)::+, one ] => {
+		$crate::core::SyncPoint::notify_one(&$($sync_point_name)::+)
+	};
+	[ $($sync_point_name:ident)::+, all ] => {
+		$crate::core::SyncPoint::notify_all(&$($sync_point_name)::+)
+	};
+}
+
+/// Synchronized code that reads the value of the named sync point
+/// declared as `rwlock<T>`, several threads (or tasks) read it at once.
+///
+/// ```rust
+///	use synchronized::sync_point;
+///	use synchronized::sync_read;
+///
+///	sync_point!(CONFIG_SYNC: rwlock<(String, usize)> = (String::new(), 0));
+///
+///	let len = sync_read!(->CONFIG_SYNC(name, _version) {
+///		name.len()
+///	});
+///	assert_eq!(len, 0);
+/// ```
+#[macro_export]
+macro_rules! sync_read {
+	{
+		// Named `$sync_point_name` synchronized block with shared references
+		// to the value of the sync point named `$v_point_name`.
+		// (Use only with `sync_point` declared as `rwlock<T>`.)
+		->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) { $($all:tt)* }
+	} => {{ // shared synchronized point
+		$crate::__sync_beh!(#new_read_lock(__lock): $($sync_point_name)::+);
+
+		let ( $(ref $v_point_name),* ) = *__lock;
+		let result = {
+			$($all)*
+		};
+		$(
+			drop($v_point_name);
+		)*
+
+		drop(__lock);
+
+		result
+	}};
+}
+
+/// Synchronized code that changes the value of the named sync point
+/// declared as `rwlock<T>`, only one thread (or task) at a time, without readers.
+///
+/// The `downgrade!();` statement turns the lock into a shared one without
+/// releasing it: readers are let in, but no writer can change the value
+/// before the end of the block. After it, the values are shared references.
+/// The statement is written at the top level of the block, only once.
+///
+/// As with `sync!`, locking the sync point again inside the block panics
+/// in debug builds (or with the `debug-checks` feature) instead of waiting forever.
+/// The `lockdep`, `track-holders` and `metrics` features and `#[max_hold]`
+/// do not cover rw sync points.
+///
+/// ```rust
+///	use synchronized::sync_point;
+///	use synchronized::sync_read;
+///	use synchronized::sync_write;
+///
+///	sync_point!(CONFIG_SYNC: rwlock<(String, usize)> = (String::new(), 0));
+///
+///	let version = sync_write!(->CONFIG_SYNC(name, version) {
+///		name.push_str("config");
+///		*version += 1;
+///		downgrade!();
+///
+///		// Readers see the new value, writers wait for the end of the block.
+///		let len = std::thread::spawn(|| {
+///			sync_read!(->CONFIG_SYNC(name, _version) {
+///				name.len()
+///			})
+///		})
+///		.join()
+///		.unwrap();
+///		assert_eq!(len, name.len());
+///		assert!(CONFIG_SYNC.try_write().is_none());
+///
+///		*version
+///	});
+///	assert_eq!(version, 1);
+/// ```
+///
+/// The values cannot be changed after `downgrade!();`:
+/// ```rust,compile_fail
+///	use synchronized::sync_point;
+///	use synchronized::sync_write;
+///
+///	sync_point!(CONFIG_SYNC: rwlock<usize> = 0);
+///
+///	sync_write!(->CONFIG_SYNC(version) {
+///		downgrade!();
+///		*version += 1;
+///	});
+/// ```
+#[macro_export]
+macro_rules! sync_write {
+	{
+		// Named `$sync_point_name` synchronized block with mutable value
+		// of synchronized name `$v_point_name`.
+		// (Use only with `sync_point` declared as `rwlock<T>`.)
+		->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* $(,)? ) { $($all:tt)* }
+	} => {
+		$crate::sync_write! {
+			@[split] ->$($sync_point_name)::+ ( $($v_point_name),* ) [] $($all)*
+		}
+	};
+
+	{
+		// `downgrade!();` is found, the rest of the block holds the shared lock.
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] downgrade!(); $($after:tt)*
+	} => {
+		$crate::sync_write! {
+			@[downgrade] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)*] $($after)*
+		}
+	};
+	{
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $t0:tt downgrade!(); $($after:tt)*
+	} => {
+		$crate::sync_write! {
+			@[downgrade] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)* $t0] $($after)*
+		}
+	};
+	{
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $t0:tt $t1:tt downgrade!(); $($after:tt)*
+	} => {
+		$crate::sync_write! {
+			@[downgrade] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)* $t0 $t1] $($after)*
+		}
+	};
+	{
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $t0:tt $t1:tt $t2:tt downgrade!(); $($after:tt)*
+	} => {
+		$crate::sync_write! {
+			@[downgrade] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)* $t0 $t1 $t2] $($after)*
+		}
+	};
+	{
+		// Collecting the block up to `downgrade!();`, four tokens at a time
+		// (none of them starts the statement, it is checked above).
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $t0:tt $t1:tt $t2:tt $t3:tt $($all:tt)*
+	} => {
+		$crate::sync_write! {
+			@[split] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)* $t0 $t1 $t2 $t3] $($all)*
+		}
+	};
+	{
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $t0:tt $($all:tt)*
+	} => {
+		$crate::sync_write! {
+			@[split] ->$($sync_point_name)::+ ( $($v_point_name),* ) [$($before)* $t0] $($all)*
+		}
+	};
+	{
+		// There is no `downgrade!();`, the whole block holds the exclusive lock.
+		@[split] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($all:tt)*]
+	} => {{ // exclusive synchronized point
+		$crate::__sync_beh!(#new_write_lock(__lock): $($sync_point_name)::+);
+
+		let ( $(ref mut $v_point_name),* ) = *__lock;
+		let result = {
+			$($all)*
+		};
+		$(
+			drop($v_point_name);
+		)*
+
+		drop(__lock);
+
+		result
+	}};
+
+	{
+		// The block is split by `downgrade!();`.
+		@[downgrade] ->$($sync_point_name:ident)::+ ( $($v_point_name: ident),* ) [$($before:tt)*] $($after:tt)*
+	} => {{ // exclusive synchronized point turned into a shared one
+		$crate::__sync_beh!(#new_write_lock(__lock): $($sync_point_name)::+);
+
+		let __read_lock;
+		let result = {
+			#[allow(unused_variables)]
+			let ( $(ref mut $v_point_name),* ) = *__lock;
+			$($before)*
+
+			// The references to the exclusive lock are no longer used.
+			__read_lock = $crate::core::RwWriteGuard::downgrade(__lock);
+			#[allow(unused_variables)]
+			let ( $(ref $v_point_name),* ) = *__read_lock;
+			$($after)*
+		};
+
+		drop(__read_lock);
+
+		result
+	}};
+}
+
+/// Turns the exclusive lock of `sync_write!` into a shared one, it is handled
+/// by `sync_write!` as a statement at the top level of its block.
+///
+/// Anywhere else (outside of `sync_write!`, in a nested block,
+/// the second time in one block) it is a compile error.
+#[macro_export]
+macro_rules! downgrade {
+	[ $($all:tt)* ] => {
+		compile_error!(
+			"`downgrade!();` can only be used once, as a statement at the top level of a `sync_write!` block"
+		);
+	};
+}
+
+/// Reset the value of the named sync point to its initial value
+/// (the expression of `sync_point`), waiting for the sync point to be released.
+///
+/// Together with `core::reset_all`, it allows isolating tests
+/// that share the global state of `sync_point`.
+///
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///	use synchronized::sync_reset;
+///
+///	sync_point!(COUNT_SYNC: usize = 0);
+///
+///	sync!(->COUNT_SYNC(count) {
+///		*count += 1;
+///	});
+///	sync_reset!(COUNT_SYNC);
+///
+///	sync!(->COUNT_SYNC(count) {
+///		assert_eq!(*count, 0);
+///	});
+///
+///	// All sync points of `sync_point` locked at least once.
+///	synchronized::core::reset_all();
+/// ```
+#[macro_export]
+macro_rules! sync_reset {
+	[ $($sync_point_name:ident)::+ ] => {
+		$crate::__sync_beh!(#reset: $($sync_point_name)::+)
+	};
+}
+
 /// Describes the selected default lock for the `synchronized` macro. Currently it is `
 #[doc = __sync_beh!( #name )]
 /// `.
 pub const CURRENT_DEF_BEH: &str = __sync_beh!( #name );
+
+/// Capabilities of the selected default lock for the `synchronized` macro.
+///
+/// Allows the library code to adapt to the lock selected through `features`,
+/// for example, to skip timeouts if they are not supported.
+pub const CURRENT_DEF_CAPABILITIES: crate::core::SyncPointCapabilities =
+	__sync_beh!( #capabilities );
+
+__sync_beh! {
+	// The default table of stripes used by `sync!(on obj, ...)`.
+	#new_striped_point<[crate::core::DEFAULT_STRIPES_LEN]>: #[doc(hidden)] pub __OBJECT_SYNC_POINT
+}
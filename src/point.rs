@@ -73,18 +73,253 @@
 ///			}
 ///		});
 ///	}}
+/// ```
+///
+/// ### 3. A named sync point defined at the module level and used from different functions.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(pub DB_SYNC: (String, usize) = (String::new(), 0));
+///
+///	fn push(name: &str) -> usize {
+///		// Synchronized code by `DB_SYNC` label, `DB_SYNC` can also be
+///		// accessed by path, for example `->crate::DB_SYNC`.
+///		sync!(->DB_SYNC(sync_let, count) {
+///			sync_let.push_str(name);
+///			*count += 1;
+///
+///			*count
+///		})
+///	}
+///
+///	fn count() -> usize {
+///		sync!(->DB_SYNC(_sync_let, count) {
+///			*count
+///		})
+///	}
+///
+///	assert_eq!(push("test"), 1);
+///	assert_eq!(count(), 1);
+/// ```
+///
+/// ### 4. A named sync point with a value created on first lock.
+/// ```rust
+///	use std::collections::HashMap;
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(pub CACHE_SYNC: HashMap<String, usize> = lazy HashMap::new());
+///
+///	let len = sync!(->CACHE_SYNC(cache) {
+///		cache.insert("test".to_string(), 1);
+///
+///		cache.len()
+///	});
+///	assert_eq!(len, 1);
+/// ```
+///
+/// ### 5. A named sync point with a separate lock for each key.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point! {USER_SYNC keyed<u64> {
+///		// Only one thread at a time for the same `user_id`.
+///		let user_id = 1;
+///		sync!(key = user_id, ->USER_SYNC {
+///			assert_eq!(USER_SYNC.len(), 1);
+///		});
+///
+///		// Locks of keys that are no longer held are removed.
+///		assert!(USER_SYNC.is_empty());
+///	}}
+/// ```
+///
+/// ### 6. A named table of 256 lock stripes to synchronize on objects.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point!(OBJECT_SYNC: striped<256>);
+///
+///	let data = [0u8; 4];
+///	let len = sync!(on &data, ->OBJECT_SYNC {
+///		data.len()
+///	});
+///	assert_eq!(len, 4);
+///	assert_eq!(OBJECT_SYNC.stripes_len(), 256);
+/// ```
+///
+/// ### 7. A named sync point read by several threads at once.
+/// ```rust
+///	use synchronized::sync_point;
+///	use synchronized::sync_read;
+///	use synchronized::sync_write;
+///
+///	sync_point!(pub CONFIG_SYNC: rwlock<Vec<String>> = Vec::new());
+///
+///	sync_write!(->CONFIG_SYNC(config) {
+///		config.push("debug".to_string());
+///	});
+///	let len = sync_read!(->CONFIG_SYNC(config) {
+///		config.len()
+///	});
+///	assert_eq!(len, 1);
+/// ```
+///
+/// ### 8. A named sync point used from plain functions without macros.
+/// ```rust
+///	use synchronized::sync_point;
+///
+///	sync_point!(pub LOG_SYNC: Vec<String> = Vec::new());
+///
+///	fn log(message: &str) {
+///		LOG_SYNC.update(|log| log.push(message.to_string()));
+///	}
+///
+///	log("start");
+///	log("stop");
+///	assert_eq!(LOG_SYNC.with(|log| log.len()), 2);
+///	assert_eq!(LOG_SYNC.get_cloned(), ["start", "stop"]);
+///	assert_eq!(LOG_SYNC.take(), ["start", "stop"]);
+///	assert_eq!(LOG_SYNC.replace(vec![String::from("new")]), Vec::<String>::new());
+///	assert_eq!(LOG_SYNC.with_try(|log| log.len()), Some(1));
+/// ```
+///
+/// ### 9. The behavior of the sync point after the code holding it panicked.
+///
+/// `#[poison = "ignore"]` (by default), `#[poison = "propagate"]` or `#[poison = "reset"]`
+/// (the value is replaced by the initial one), see `PoisonPolicy`.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point! {
+///		#[poison = "reset"]
+///		COUNT_SYNC: usize = 0;
+///
+///		#[poison = "propagate"]
+///		TOTAL_SYNC: usize = 0
+///	}
+///
+///	let _e = std::thread::spawn(|| {
+///		sync!(->COUNT_SYNC(count) {
+///			*count += 1;
+///			if *count > 0 {
+///				panic!("the count is inconsistent");
+///			}
+///		});
+///	})
+///	.join();
+///	// The count was reset to the initial value.
+///	assert_eq!(COUNT_SYNC.get_cloned(), 0);
+///
+///	let _e = std::thread::spawn(|| {
+///		sync!(->TOTAL_SYNC(total) {
+///			*total += 1;
+///			if *total > 0 {
+///				panic!("the total is inconsistent");
+///			}
+///		});
+///	})
+///	.join();
+///	assert!(TOTAL_SYNC.lock_checked().is_err());
+///
+///	TOTAL_SYNC.clear_poison();
+///	assert_eq!(TOTAL_SYNC.get_cloned(), 1);
+/// ```
+///
+/// ### 10. Budget of the time the sync point may be held.
+///
+/// `#[max_hold = "10ms"]` (`ns`, `us`, `ms`, `s` or `m`), the blocks exceeding it
+/// are reported when the sync point is released (only then, a block that never
+/// releases it is not reported), see `set_hold_overrun_callback`.
+/// ```rust
+///	use synchronized::sync;
+///	use synchronized::sync_point;
+///
+///	sync_point! {
+///		#[max_hold = "10s"]
+///		COUNT_SYNC: usize = 0
+///	}
+///
+///	sync!(->COUNT_SYNC(count) {
+///		*count += 1;
+///	});
+///	assert_eq!(COUNT_SYNC.max_hold(), Some(std::time::Duration::from_secs(10)));
+/// ```
+///
+/// `#[poison]` and `#[max_hold]` are not supported on `striped`, `keyed` and `rwlock` sync points:
+/// ```rust,compile_fail
+///	use synchronized::sync_point;
+///
+///	sync_point! {
+///		#[max_hold = "10s"]
+///		CONFIG_SYNC: rwlock<usize> = 0
+///	}
+/// ```
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "point")))]
 macro_rules! sync_point {
+	{
+		// Named sync point named `$sync_point_name`.
+		//
+		// With mutable synchronized comma-separated variables of type `$ty`
+		// with a value created by `$expr` on first lock.
+		@[configured] [$($config:tt)*] $sync_point_name:ident ( $($ty: ty = lazy $expr:expr),* $(,)? ) {$($all:tt)*} $(; $($unk:tt)*)?
+	} => {
+		{
+			$crate::__sync_beh!(#new_lazy_point<($($ty),*): [($($expr),*)]>: pub $sync_point_name $($config)*);
+
+			$($all)*
+		}
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named table of `$stripes_len` sync points named `$sync_point_name`
+		// used to synchronize on object instances.
+		@[configured] [$($config:tt)*] $sync_point_name:ident striped<$stripes_len: tt> {$($all:tt)*} $(; $($unk:tt)*)?
+	} => {
+		{
+			$crate::sync_point!(@[unsupported_config] [$($config)*]);
+			$crate::__sync_beh!(#new_striped_point<[$stripes_len]>: pub $sync_point_name);
+
+			$($all)*
+		}
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` with a separate lock
+		// for each key of type `$key_ty`.
+		@[configured] [$($config:tt)*] $sync_point_name:ident keyed<$key_ty: ty> {$($all:tt)*} $(; $($unk:tt)*)?
+	} => {
+		{
+			$crate::sync_point!(@[unsupported_config] [$($config)*]);
+			$crate::__sync_beh!(#new_keyed_point<$key_ty>: pub $sync_point_name);
+
+			$($all)*
+		}
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
 	{
 		// Named sync point named `$sync_point_name`.
 		//
 		// With a mutable synchronized variable of type `$ty`
 		// with a default value of `$expr`.
-		$sync_point_name:ident ( $ty: ty = $expr:expr $(,)? ) {$($all:tt)*} $(; $($unk:tt)*)?
+		@[configured] [$($config:tt)*] $sync_point_name:ident ( $ty: ty = $expr:expr $(,)? ) {$($all:tt)*} $(; $($unk:tt)*)?
 	} => {
 		{
-			$crate::__sync_beh!(#new_point<$ty: [$expr]>: $sync_point_name);
+			$crate::__sync_beh!(#new_point<$ty: [$expr]>: pub $sync_point_name $($config)*);
 
 			$($all)*
 		}
@@ -98,10 +333,10 @@ macro_rules! sync_point {
 		//
 		// With mutable synchronized comma-separated variables of type `$ty`
 		// with a default value of `$expr`.
-		$sync_point_name:ident ( $($ty: ty = $expr:expr),* $(,)? ) {$($all:tt)*} $(; $($unk:tt)*)?
+		@[configured] [$($config:tt)*] $sync_point_name:ident ( $($ty: ty = $expr:expr),* $(,)? ) {$($all:tt)*} $(; $($unk:tt)*)?
 	} => {
 		{
-			$crate::__sync_beh!(#new_point<($($ty),*): [($($expr),*)]>: $sync_point_name);
+			$crate::__sync_beh!(#new_point<($($ty),*): [($($expr),*)]>: pub $sync_point_name $($config)*);
 
 			$($all)*
 		}
@@ -112,10 +347,83 @@ macro_rules! sync_point {
 	};
 	{
 		// Named sync point named `$sync_point_name`
-		($sync_point_name:ident) {$($all:tt)*} $(; $($unk:tt)*)?
+		@[configured] [$($config:tt)*] ($sync_point_name:ident) {$($all:tt)*} $(; $($unk:tt)*)?
+	} => {
+		$crate::sync_point! {
+			@[configured] [$($config)*] $sync_point_name (() = ()) { $($all)* }
+
+			$(; $($unk)*)?
+		}
+	};
+
+	{
+		// Named table of `$stripes_len` sync points named `$sync_point_name`
+		// defined at the module level, used to synchronize on object instances.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident: striped<$stripes_len: tt> $(; $($unk:tt)*)?
+	} => {
+		$crate::sync_point!(@[unsupported_config] [$($config)*]);
+		$crate::__sync_beh!(#new_striped_point<[$stripes_len]>: $(#[$meta])* $vis $sync_point_name);
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` defined at the module level
+		// with a value of type `$ty` shared by readers and held exclusively by writers.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident: rwlock<$ty: ty> = $expr:expr $(; $($unk:tt)*)?
+	} => {
+		$crate::sync_point!(@[unsupported_config] [$($config)*]);
+		$crate::__sync_beh!(#new_rw_point<$ty: [$expr]>: $(#[$meta])* $vis $sync_point_name);
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` defined at the module level
+		// with a separate lock for each key of type `$key_ty`.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident: keyed<$key_ty: ty> $(; $($unk:tt)*)?
+	} => {
+		$crate::sync_point!(@[unsupported_config] [$($config)*]);
+		$crate::__sync_beh!(#new_keyed_point<$key_ty>: $(#[$meta])* $vis $sync_point_name);
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` defined at the module level.
+		//
+		// With a mutable synchronized variable of type `$ty`
+		// with a value created by `$expr` on first lock.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident: $ty: ty = lazy $expr:expr $(; $($unk:tt)*)?
+	} => {
+		$crate::__sync_beh!(#new_lazy_point<$ty: [$expr]>: $(#[$meta])* $vis $sync_point_name $($config)*);
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` defined at the module level.
+		//
+		// With a mutable synchronized variable of type `$ty`
+		// with a default value of `$expr`.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident: $ty: ty = $expr:expr $(; $($unk:tt)*)?
+	} => {
+		$crate::__sync_beh!(#new_point<$ty: [$expr]>: $(#[$meta])* $vis $sync_point_name $($config)*);
+
+		$($crate::sync_point! {
+			$($unk)*
+		})?
+	};
+	{
+		// Named sync point named `$sync_point_name` defined at the module level.
+		@[configured] [$($config:tt)*] $(#[$meta:meta])* $vis:vis $sync_point_name:ident $(; $($unk:tt)*)?
 	} => {
 		$crate::sync_point! {
-			$sync_point_name (() = ()) { $($all)* }
+			@[configured] [$($config)*] $(#[$meta])* $vis $sync_point_name: () = ()
 
 			$(; $($unk)*)?
 		}
@@ -123,7 +431,7 @@ macro_rules! sync_point {
 
 	{
 		// COMPILE_ERROR
-		$($unk:tt)+
+		@[configured] [$($config:tt)*] $($unk:tt)+
 	} => {
 		compile_error!(concat!(
 			"Error writing macro `sync_point`, incode: ",
@@ -131,5 +439,69 @@ macro_rules! sync_point {
 		));
 	};
 
+	{
+		// The sync point does not take the configuration (striped, keyed, rwlock).
+		@[unsupported_config] []
+	} => {};
+	{
+		@[unsupported_config] [$($config:tt)+]
+	} => {
+		compile_error!("#[poison]/#[max_hold] is not supported on striped/keyed/rwlock sync points");
+	};
+
+	{
+		// Collecting the configuration of the sync point from the attributes:
+		// the behavior after the code holding the sync point panicked.
+		@[config] [$($config:tt)*] [$($meta:tt)*] #[poison = "ignore"] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [$($config)* .with_poison_policy($crate::core::PoisonPolicy::Ignore)] [$($meta)*] $($all)+
+		}
+	};
+	{
+		@[config] [$($config:tt)*] [$($meta:tt)*] #[poison = "propagate"] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [$($config)* .with_poison_policy($crate::core::PoisonPolicy::Propagate)] [$($meta)*] $($all)+
+		}
+	};
+	{
+		@[config] [$($config:tt)*] [$($meta:tt)*] #[poison = "reset"] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [$($config)* .with_poison_policy($crate::core::PoisonPolicy::Reset)] [$($meta)*] $($all)+
+		}
+	};
+	{
+		@[config] [$($config:tt)*] [$($meta:tt)*] #[max_hold = $max_hold:literal] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [$($config)* .with_max_hold($crate::core::parse_max_hold($max_hold))] [$($meta)*] $($all)+
+		}
+	};
+	{
+		// Other attributes are passed to the sync point.
+		@[config] [$($config:tt)*] [$($meta:tt)*] #[$new_meta:meta] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [$($config)*] [$($meta)* #[$new_meta]] $($all)+
+		}
+	};
+	{
+		// The configuration is collected.
+		@[config] [$($config:tt)*] [$($meta:tt)*] $($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[configured] [$($config)*] $($meta)* $($all)+
+		}
+	};
+	{
+		$($all:tt)+
+	} => {
+		$crate::sync_point! {
+			@[config] [] [] $($all)+
+		}
+	};
+
 	[] => {}
 }
@@ -0,0 +1,384 @@
+//Copyright 2022-2025 #UlinProject Denis Kotlyarov (Денис Котляров)
+
+//Licensed under the Apache License, Version 2.0 (the "License");
+//you may not use this file except in compliance with the License.
+//You may obtain a copy of the License at
+
+//	   http://www.apache.org/licenses/LICENSE-2.0
+
+//Unless required by applicable law or agreed to in writing, software
+//distributed under the License is distributed on an "AS IS" BASIS,
+//WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//See the License for the specific language governing permissions and
+// limitations under the License.
+
+// #Ulin Project 2022-2025
+//
+
+//! The `#[synchronized]` attribute for the `synchronized` library,
+//! use it through `synchronized::synchronized` (`attr` feature).
+
+use proc_macro::TokenStream;
+use proc_macro2::Group;
+use proc_macro2::Span;
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use quote::quote;
+use quote::quote_spanned;
+use syn::Attribute;
+use syn::Block;
+use syn::ImplItem;
+use syn::Item;
+use syn::ItemImpl;
+use syn::Path;
+use syn::ReturnType;
+use syn::Signature;
+use syn::Token;
+use syn::Type;
+use syn::TypeImplTrait;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::parse_macro_input;
+use syn::visit::Visit;
+use syn::visit_mut::VisitMut;
+
+/// Which sync point the function is synchronized on.
+enum SyncMode {
+	/// Own anonymous sync point of the function
+	/// (a sync point shared by all methods of the type for `impl` blocks).
+	Anonymous,
+	/// Sync point shared by all methods of the type `Self`
+	/// (written with `'static` lifetimes) in all its `impl` blocks.
+	Monitor(Type),
+	/// Shared named sync point (`sync_point`).
+	Point(Path),
+	/// Object instance `&self` (`sync!(on self, ...)`).
+	Instance,
+	/// The function is not synchronized.
+	Skip,
+}
+
+impl SyncMode {
+	/// The mode named by `path`.
+	fn from_path(path: Path) -> Self {
+		if path.is_ident("instance") {
+			return Self::Instance;
+		}
+		if path.is_ident("skip") {
+			return Self::Skip;
+		}
+
+		Self::Point(path)
+	}
+}
+
+/// Arguments of the attribute: `#[synchronized(mode, crate = path)]`, both are optional.
+struct SyncArgs {
+	mode: SyncMode,
+	/// Path to the `synchronized` library (`crate = path`), if it is not `::synchronized`.
+	krate: Option<Path>,
+}
+
+impl Parse for SyncArgs {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut mode = None;
+		let mut krate = None;
+		while !input.is_empty() {
+			if input.peek(Token![crate]) && input.peek2(Token![=]) {
+				let crate_token = input.parse::<Token![crate]>()?;
+				input.parse::<Token![=]>()?;
+				if krate.replace(input.parse()?).is_some() {
+					return Err(syn::Error::new_spanned(
+						crate_token,
+						"`crate` is specified more than once",
+					));
+				}
+			} else {
+				let path: Path = input.parse()?;
+				if mode.is_some() {
+					return Err(syn::Error::new_spanned(
+						path,
+						"the sync point is specified more than once",
+					));
+				}
+				mode = Some(SyncMode::from_path(path));
+			}
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(Self {
+			mode: mode.unwrap_or(SyncMode::Anonymous),
+			krate,
+		})
+	}
+}
+
+/// Turns the whole function into a synchronized block.
+///
+/// `#[synchronized]` synchronizes the function on its own anonymous sync point,
+/// `#[synchronized(POINT)]` on the shared named sync point `POINT`,
+/// `#[synchronized(instance)]` on the object `&self` (as `sync!(on self, ...)`).
+///
+/// On an `impl` block, all methods of the block share one generated sync point
+/// (or are synchronized on `&self` with `#[synchronized(instance)]`),
+/// methods marked `#[synchronized(skip)]` are left unsynchronized.
+/// The generated sync point belongs to the type: all `#[synchronized]` `impl` blocks
+/// of the type share it, each set of type arguments of a generic type has its own.
+/// It is stored in `core::registry` under the type, so the type arguments must be `'static`
+/// (lifetimes of the type itself do not matter).
+///
+/// The locks are reentrant (`Reentrant`), a synchronized function can call
+/// functions synchronized on the same sync point (or itself) without waiting for itself,
+/// only plain `sync!` blocks holding the sync point still do.
+///
+/// The body is executed in a closure (an async closure for `async fn`),
+/// so `return` and `?` leave only the body and the lock is always released
+/// in the same way as at the end of `sync!`.
+///
+/// `crate = path` sets the path to the `synchronized` library
+/// if it is not available as `::synchronized` (for example, it is re-exported),
+/// `#[synchronized(DB_SYNC, crate = my_lib::synchronized)]`.
+#[proc_macro_attribute]
+pub fn synchronized(attr: TokenStream, item: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(attr as SyncArgs);
+	let item = parse_macro_input!(item as Item);
+	let krate = args
+		.krate
+		.unwrap_or_else(|| syn::parse_quote!(::synchronized));
+
+	let result = match item {
+		Item::Fn(mut item_fn) => {
+			synchronized_fn(&args.mode, &krate, &item_fn.sig, &mut item_fn.block, true)
+				.map(|()| item_fn.into_token_stream())
+		}
+		Item::Impl(item_impl) => synchronized_impl(&args.mode, &krate, item_impl),
+		item => Err(syn::Error::new_spanned(
+			item,
+			"`#[synchronized]` can only be used with functions and `impl` blocks",
+		)),
+	};
+
+	match result {
+		Ok(a) => a.into(),
+		Err(e) => e.into_compile_error().into(),
+	}
+}
+
+/// Synchronizes all methods of `item_impl` according to `mode`,
+/// methods with their own `#[synchronized(...)]` according to it.
+fn synchronized_impl(
+	mode: &SyncMode,
+	krate: &Path,
+	mut item_impl: ItemImpl,
+) -> syn::Result<TokenStream2> {
+	let method_mode = match mode {
+		SyncMode::Anonymous => {
+			let mut self_ty = (*item_impl.self_ty).clone();
+			StaticLifetimes.visit_type_mut(&mut self_ty);
+
+			SyncMode::Monitor(self_ty)
+		}
+		SyncMode::Monitor(self_ty) => SyncMode::Monitor(self_ty.clone()),
+		SyncMode::Point(path) => SyncMode::Point(path.clone()),
+		SyncMode::Instance => SyncMode::Instance,
+		SyncMode::Skip => return Ok(item_impl.into_token_stream()),
+	};
+
+	for impl_item in &mut item_impl.items {
+		let ImplItem::Fn(method) = impl_item else {
+			continue;
+		};
+
+		match method_attr(&mut method.attrs)? {
+			// The method has its own `#[synchronized(...)]`.
+			Some(args) => synchronized_fn(
+				&args.mode,
+				args.krate.as_ref().unwrap_or(krate),
+				&method.sig,
+				&mut method.block,
+				true,
+			)?,
+			None => synchronized_fn(&method_mode, krate, &method.sig, &mut method.block, false)?,
+		}
+	}
+
+	Ok(item_impl.into_token_stream())
+}
+
+/// Replaces all lifetimes of a type with `'static`,
+/// the type is only used as the key of the registry.
+struct StaticLifetimes;
+
+impl VisitMut for StaticLifetimes {
+	fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+		*lifetime = syn::Lifetime::new("'static", lifetime.span());
+	}
+}
+
+/// Arguments of the `#[synchronized(...)]` attribute of the method,
+/// the attribute is removed from the method.
+fn method_attr(attrs: &mut Vec<Attribute>) -> syn::Result<Option<SyncArgs>> {
+	let Some(index) = attrs.iter().position(|attr| {
+		attr.path()
+			.segments
+			.last()
+			.is_some_and(|segment| segment.ident == "synchronized")
+	}) else {
+		return Ok(None);
+	};
+
+	let args = match &attrs[index].meta {
+		syn::Meta::Path(_) => SyncArgs {
+			mode: SyncMode::Anonymous,
+			krate: None,
+		},
+		_ => attrs[index].parse_args()?,
+	};
+	attrs.remove(index);
+
+	Ok(Some(args))
+}
+
+/// Whether `ty` contains `impl Trait`.
+fn contains_impl_trait(ty: &Type) -> bool {
+	struct ImplTraitFinder(bool);
+
+	impl Visit<'_> for ImplTraitFinder {
+		fn visit_type_impl_trait(&mut self, _: &TypeImplTrait) {
+			self.0 = true;
+		}
+	}
+
+	let mut finder = ImplTraitFinder(false);
+	finder.visit_type(ty);
+
+	finder.0
+}
+
+/// `tokens` with all spans set to `span`, the location of a macro call
+/// is taken from the path of the macro.
+fn respan(tokens: TokenStream2, span: Span) -> TokenStream2 {
+	tokens
+		.into_iter()
+		.map(|mut token| {
+			if let TokenTree::Group(group) = &token {
+				let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+				respanned.set_span(span);
+				token = TokenTree::Group(respanned);
+			}
+			token.set_span(span);
+			token
+		})
+		.collect()
+}
+
+/// Replaces `block` (the body of the function `sig`)
+/// with the body synchronized according to `mode`.
+///
+/// Methods that cannot be synchronized on the object (without `&self`)
+/// are an error for a single function (`is_fn`) and are skipped in `impl` blocks.
+fn synchronized_fn(
+	mode: &SyncMode,
+	krate: &Path,
+	sig: &Signature,
+	block: &mut Block,
+	is_fn: bool,
+) -> syn::Result<()> {
+	if let Some(constness) = &sig.constness {
+		return Err(syn::Error::new_spanned(
+			constness,
+			"`#[synchronized]` cannot be used with `const fn`",
+		));
+	}
+	if matches!(mode, SyncMode::Skip) {
+		return Ok(());
+	}
+	if matches!(mode, SyncMode::Instance) {
+		// Only the reference to the object identifies it, a method that takes
+		// `self` by value owns the object and does not need to be synchronized.
+		let is_ref_receiver = sig
+			.receiver()
+			.is_some_and(|receiver| matches!(*receiver.ty, Type::Reference(_)));
+		if !is_ref_receiver {
+			if is_fn {
+				return Err(syn::Error::new_spanned(
+					sig,
+					"`#[synchronized(instance)]` requires a method that takes `&self` or `&mut self`",
+				));
+			}
+
+			return Ok(());
+		}
+	}
+
+	// `impl Trait` cannot be written as the return type of a closure,
+	// in this case the type is inferred.
+	let output = match &sig.output {
+		ReturnType::Type(_, ty) if contains_impl_trait(ty) => TokenStream2::new(),
+		output => output.to_token_stream(),
+	};
+	let body = match sig.asyncness {
+		Some(_) => quote! {
+			#krate::attr::call_async(async move || #output #block).await
+		},
+		None => quote! {
+			#krate::attr::call(move || #output #block)
+		},
+	};
+
+	// The sync point is locked (and reported by the checks) at the function.
+	let span = sig.ident.span();
+	let krate = respan(krate.to_token_stream(), span);
+	let pound = <Token![#]>::default();
+	let point = match mode {
+		SyncMode::Anonymous => quote_spanned! {span=>
+			#krate::__sync_beh! {
+				#pound new_point<(): [()]>: __ANONYMOUS_SYNC_POINT
+			}
+			let __point = #krate::core::Reentrant::new(&__ANONYMOUS_SYNC_POINT);
+		},
+		SyncMode::Monitor(self_ty) => quote_spanned! {span=>
+			#krate::__sync_beh! {
+				#pound new_typed_point<(): [()]>: (#krate::attr::Monitor, #self_ty) => __SYNCHRONIZED_MONITOR
+			}
+			let __point = #krate::core::Reentrant::new(__SYNCHRONIZED_MONITOR);
+		},
+		SyncMode::Point(path) => quote_spanned! {span=>
+			let __point = #krate::core::Reentrant::new(&#path);
+		},
+		SyncMode::Instance => {
+			// The stripes of objects are locked reentrantly by `sync!(on ...)`.
+			*block = syn::parse_quote_spanned! {span=>
+				{
+					#krate::sync! {
+						on self, {
+							#body
+						}
+					}
+				}
+			};
+
+			return Ok(());
+		}
+		SyncMode::Skip => unreachable!(),
+	};
+
+	let synchronized_body = quote_spanned! {span=>
+		#point
+		#krate::__sync_beh!(#pound new_lock(__lock): __point);
+
+		let result = #body;
+
+		#krate::__sync_beh!(#pound drop_lock(__lock): __point);
+
+		result
+	};
+
+	*block = syn::parse_quote!({ #synchronized_body });
+
+	Ok(())
+}
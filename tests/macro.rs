@@ -52,3 +52,2053 @@ mod test_noasync_onlypoints {
 		});
 	}
 }
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_capabilities {
+	use synchronized::CURRENT_DEF_CAPABILITIES;
+	use synchronized::sync_point;
+
+	#[test]
+	fn test_capabilities() {
+		sync_point! ((NAME_SYNC_POINT) {
+			let capabilities = NAME_SYNC_POINT.capabilities();
+
+			assert_eq!(capabilities, CURRENT_DEF_CAPABILITIES);
+			assert!(capabilities.try_lock);
+			assert!(!capabilities.is_async);
+			assert!(!capabilities.reentrant);
+			assert!(capabilities.poisoning);
+		});
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_module_points {
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(pub DB_SYNC: (String, usize) = (String::new(), 0));
+
+	mod inner {
+		use synchronized::sync;
+
+		pub fn push(value: &str) -> usize {
+			sync!(->super::DB_SYNC(sync_let, count) {
+				sync_let.push_str(value);
+				*count += 1;
+
+				*count
+			})
+		}
+	}
+
+	#[test]
+	fn test_module_sync_point() {
+		assert_eq!(inner::push("a"), 1);
+		assert_eq!(inner::push("b"), 2);
+
+		let result = sync!(->crate::test_module_points::DB_SYNC(sync_let, count) {
+			assert_eq!(count, &2);
+
+			sync_let.clone()
+		});
+		assert_eq!(result, "ab");
+	}
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod test_lazy {
+	use std::collections::HashMap;
+	use synchronized::sync;
+
+	fn sync_fn(key: &str) -> usize {
+		sync!((cache: HashMap<String, usize> = lazy HashMap::new(), count: usize = lazy 0) {
+			*count += 1;
+
+			*cache.entry(key.to_string()).or_insert(*count)
+		})
+	}
+
+	#[test]
+	fn test_lazy_sync_value() {
+		assert_eq!(sync_fn("a"), 1);
+		assert_eq!(sync_fn("b"), 2);
+		assert_eq!(sync_fn("a"), 1);
+	}
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod test_generic {
+	use synchronized::sync;
+
+	fn sync_shared<T>(_value: T) -> usize {
+		sync!((count: usize = 0) {
+			*count += 1;
+
+			*count
+		})
+	}
+
+	fn sync_typed<T: 'static>() -> usize {
+		sync!(<T> (count: usize = 0) {
+			*count += 1;
+
+			*count
+		})
+	}
+
+	fn sync_typed_nested<T: 'static>() -> usize {
+		// The initial value is created by another typed sync point.
+		sync!(<T> (count: usize = sync_typed::<T>() * 10) {
+			*count += 1;
+
+			*count
+		})
+	}
+
+	#[test]
+	fn test_generic_sync_point() {
+		assert_eq!(sync_shared(1u8), 1);
+		assert_eq!(sync_shared(1u16), 2);
+
+		assert_eq!(sync_typed::<u8>(), 1);
+		assert_eq!(sync_typed::<u16>(), 1);
+		assert_eq!(sync_typed::<u8>(), 2);
+	}
+
+	#[test]
+	fn test_generic_nested_init() {
+		struct Key;
+
+		assert_eq!(sync_typed_nested::<Key>(), 11);
+		assert_eq!(sync_typed_nested::<Key>(), 12);
+		assert_eq!(sync_typed::<Key>(), 2);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_try_sync {
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::try_sync;
+
+	#[test]
+	fn test_try_sync() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let result = sync!(->COMB_SYNC(count) {
+			let result = std::thread::scope(|scope| {
+				scope
+					.spawn(|| try_sync!((->COMB_SYNC(count)) { *count += 1; }))
+					.join()
+					.unwrap()
+			});
+			*count += 1;
+
+			result
+		});
+		assert_eq!(result, None);
+
+		let result = try_sync!((->COMB_SYNC(count)) {
+			*count += 1;
+
+			*count
+		});
+		assert_eq!(result, Some(2));
+
+		let result = try_sync! {
+			1 + 2
+		};
+		assert_eq!(result, Some(3));
+	}
+}
+
+#[cfg(all(test, feature = "async", any(feature = "std", feature = "pl")))]
+mod test_tokio_mutex_sync {
+	use std::time::Duration;
+	use synchronized::beh::r#async::Mutex;
+	use synchronized::core::SyncPointBeh;
+
+	#[test]
+	fn test_default_try_lock_for() {
+		// The `tokio` mutex used by the synchronous version of the library.
+		let mutex = Mutex::new(0);
+
+		let lock = SyncPointBeh::try_lock(&mutex).unwrap();
+		assert!(SyncPointBeh::try_lock_for(&mutex, Duration::from_millis(10)).is_none());
+		drop(lock);
+		assert!(SyncPointBeh::try_lock_for(&mutex, Duration::from_millis(10)).is_some());
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_timeout {
+	use std::time::Duration;
+	use std::time::Instant;
+	use synchronized::core::SyncPoint;
+	use synchronized::core::SyncPointBeh;
+	use synchronized::core::TimedOut;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	/// Implements only the required methods of `SyncPointBeh`.
+	struct PlainMutex(std::sync::Mutex<usize>);
+
+	impl SyncPointBeh for PlainMutex {
+		type LockType<'a> = std::sync::MutexGuard<'a, usize>;
+		type DerefLockType = usize;
+
+		fn new_lock(&self) -> Self::LockType<'_> {
+			self.0.lock().unwrap()
+		}
+
+		fn try_lock(&self) -> Option<Self::LockType<'_>> {
+			self.0.try_lock().ok()
+		}
+
+		fn unlock(&self, lock_type: Self::LockType<'_>) {
+			drop(lock_type)
+		}
+
+		#[cfg(all(feature = "pl", not(feature = "std")))]
+		fn is_lock(&self) -> bool {
+			self.0.try_lock().is_err()
+		}
+	}
+
+	#[test]
+	fn test_sync_timeout() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let result = sync!(->COMB_SYNC(count) {
+			std::thread::scope(|scope| {
+				scope
+					.spawn(|| {
+						let time = Instant::now();
+						let result = sync!(timeout = Duration::from_millis(20), ->COMB_SYNC(count) {
+							*count += 1;
+						});
+						assert!(time.elapsed() >= Duration::from_millis(20));
+
+						result
+					})
+					.join()
+					.unwrap()
+			})
+		});
+		assert_eq!(result, Err(TimedOut));
+
+		let result = std::thread::scope(|scope| {
+			let join = sync!(->COMB_SYNC(count) {
+				let join = scope.spawn(|| {
+					sync!(timeout = Duration::from_secs(10), ->COMB_SYNC(count) {
+						*count += 1;
+
+						*count
+					})
+				});
+				std::thread::sleep(Duration::from_millis(10));
+				*count += 1;
+
+				join
+			});
+
+			join.join().unwrap()
+		});
+		assert_eq!(result, Ok(2));
+	}
+
+	#[test]
+	fn test_default_try_lock_for() {
+		let point = SyncPoint::new(PlainMutex(std::sync::Mutex::new(0)));
+		assert!(point.capabilities().timed_lock);
+
+		let lock = point.new_lock();
+		std::thread::scope(|scope| {
+			scope.spawn(|| {
+				let time = Instant::now();
+				assert!(point.try_lock_for(Duration::from_millis(20)).is_none());
+				assert!(time.elapsed() >= Duration::from_millis(20));
+			});
+		});
+		drop(lock);
+
+		let mut lock = point.try_lock_for(Duration::from_millis(20)).unwrap();
+		*lock += 1;
+		assert_eq!(*lock, 1);
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_timeout {
+	use std::time::Duration;
+	use std::time::Instant;
+	use synchronized::core::TimedOut;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	#[tokio::test]
+	async fn test_sync_timeout() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let result = sync!(->COMB_SYNC(_count) {
+			tokio::spawn(async {
+				let time = Instant::now();
+				let result = sync!(timeout = Duration::from_millis(20), ->COMB_SYNC(count) {
+					*count += 1;
+				});
+				assert!(time.elapsed() >= Duration::from_millis(20));
+
+				result
+			})
+			.await
+			.unwrap()
+		});
+		assert_eq!(result, Err(TimedOut));
+
+		let join = sync!(->COMB_SYNC(count) {
+			let join = tokio::spawn(async {
+				sync!(timeout = Duration::from_secs(10), ->COMB_SYNC(count) {
+					*count += 1;
+
+					*count
+				})
+			});
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			*count += 1;
+
+			join
+		});
+		assert_eq!(join.await.unwrap(), Ok(2));
+	}
+
+	#[tokio::test]
+	async fn test_try_lock_for() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let lock = COMB_SYNC.new_lock().await;
+		assert!(
+			COMB_SYNC
+				.try_lock_for(Duration::from_millis(10))
+				.await
+				.is_none()
+		);
+		drop(lock);
+
+		let mut lock = COMB_SYNC
+			.try_lock_for(Duration::from_millis(10))
+			.await
+			.unwrap();
+		*lock += 1;
+		assert_eq!(*lock, 1);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_cancel {
+	use std::time::Duration;
+	use synchronized::core::CancelToken;
+	use synchronized::core::Cancelled;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	#[test]
+	fn test_sync_cancel() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let token = CancelToken::new();
+		let result = std::thread::scope(|scope| {
+			sync!(->COMB_SYNC(count) {
+				let join = scope.spawn(|| {
+					sync!(cancel = &token, ->COMB_SYNC(count) {
+						*count += 1;
+					})
+				});
+				std::thread::sleep(Duration::from_millis(10));
+				token.cancel();
+
+				let result = join.join().unwrap();
+				assert_eq!(count, &0);
+
+				result
+			})
+		});
+		assert_eq!(result, Err(Cancelled));
+		assert!(token.is_cancelled());
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_cancel {
+	use std::time::Duration;
+	use synchronized::core::CancelToken;
+	use synchronized::core::Cancelled;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	#[tokio::test]
+	async fn test_sync_cancel() {
+		sync_point!(COMB_SYNC: usize = 0);
+
+		let token = CancelToken::new();
+		let result = sync!(->COMB_SYNC(count) {
+			let join = tokio::spawn({
+				let token = token.clone();
+
+				async move {
+					sync!(cancel = &token, ->COMB_SYNC(count) {
+						*count += 1;
+					})
+				}
+			});
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			token.cancel();
+
+			let result = join.await.unwrap();
+			assert_eq!(count, &0);
+
+			result
+		});
+		assert_eq!(result, Err(Cancelled));
+		assert!(token.is_cancelled());
+
+		// A cancelled token does not even lock a free sync point.
+		let result = sync!(cancel = &token, ->COMB_SYNC(count) {
+			*count += 1;
+		});
+		assert_eq!(result, Err(Cancelled));
+
+		let token = CancelToken::new();
+		let result = sync!(cancel = &token, ->COMB_SYNC(count) {
+			*count += 1;
+
+			*count
+		});
+		assert_eq!(result, Ok(1));
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_multi_points {
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(ACCOUNTS: Vec<usize> = Vec::new(); LEDGER: usize = 0; AUDIT);
+
+	#[test]
+	fn test_sync_multi_points() {
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				scope.spawn(move || {
+					for _ in 0..100 {
+						if i % 2 == 0 {
+							sync!((->ACCOUNTS(accounts), ->LEDGER(ledger), ->AUDIT) {
+								accounts.push(1);
+								*ledger += 1;
+							});
+						} else {
+							sync!((->AUDIT, ->LEDGER(ledger), ->ACCOUNTS(accounts)) {
+								accounts.push(1);
+								*ledger += 1;
+							});
+						}
+					}
+				});
+			}
+		});
+
+		let result = sync!((->LEDGER(ledger), ->ACCOUNTS(accounts)) {
+			assert_eq!(accounts.len(), *ledger);
+
+			*ledger
+		});
+		assert_eq!(result, 800);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_keyed {
+	use std::time::Duration;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(USER_SYNC: keyed<u64>);
+
+	#[test]
+	fn test_sync_keyed() {
+		static mut COUNTS: [usize; 2] = [0; 2];
+
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				scope.spawn(move || {
+					let user_id = i % 2;
+					for _ in 0..10 {
+						sync!(key = user_id, ->USER_SYNC {
+							unsafe {
+								let count = COUNTS[user_id as usize];
+								std::thread::sleep(Duration::from_micros(10));
+								COUNTS[user_id as usize] = count + 1;
+							}
+						});
+					}
+				});
+			}
+		});
+
+		assert_eq!(unsafe { COUNTS }, [40, 40]);
+		assert!(USER_SYNC.is_empty());
+
+		let result = sync!(key = "test", { sync!(key = "other", { 1 + 2 }) });
+		assert_eq!(result, 3);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_on_object {
+	use std::cell::UnsafeCell;
+	use std::sync::Arc;
+	use std::time::Duration;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(OBJECT_SYNC: striped<8>);
+	sync_point!(SHARED_SYNC: striped<1>);
+
+	struct Counter {
+		count: UnsafeCell<usize>,
+	}
+
+	unsafe impl Sync for Counter {}
+
+	impl Counter {
+		const fn new() -> Self {
+			Self {
+				count: UnsafeCell::new(0),
+			}
+		}
+
+		fn inc(&self) {
+			sync!(on self, {
+				unsafe { self.inc_unsync() }
+			})
+		}
+
+		/// Increment that loses updates if it is executed in parallel.
+		unsafe fn inc_unsync(&self) {
+			unsafe {
+				let count = *self.count.get();
+				std::thread::sleep(Duration::from_micros(10));
+				*self.count.get() = count + 1;
+			}
+		}
+
+		fn get(&self) -> usize {
+			unsafe { *self.count.get() }
+		}
+	}
+
+	/// The object is the counter, not the variable `counter` of each thread.
+	fn inc_ref(counter: &Counter) {
+		sync!(on &counter, ->OBJECT_SYNC {
+			unsafe { counter.inc_unsync() }
+		})
+	}
+
+	#[test]
+	fn test_sync_on_object() {
+		let counters = [Counter::new(), Counter::new()];
+
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				let counter = &counters[i % 2];
+				scope.spawn(move || {
+					for _ in 0..10 {
+						counter.inc();
+					}
+				});
+			}
+		});
+
+		for counter in &counters {
+			assert_eq!(counter.get(), 40);
+		}
+
+		let result = sync!(on &counters[0], ->OBJECT_SYNC {
+			sync!(on &counters[1], { 1 + 2 })
+		});
+		assert_eq!(result, 3);
+	}
+
+	#[test]
+	fn test_sync_on_reference() {
+		let counter = Counter::new();
+
+		std::thread::scope(|scope| {
+			for _ in 0..8 {
+				scope.spawn(|| {
+					for _ in 0..10 {
+						inc_ref(&counter);
+					}
+				});
+			}
+		});
+		assert_eq!(counter.get(), 80);
+
+		let counter = Arc::new(Counter::new());
+		std::thread::scope(|scope| {
+			for _ in 0..8 {
+				let counter = counter.clone();
+				scope.spawn(move || {
+					for _ in 0..10 {
+						sync!(on &counter, ->OBJECT_SYNC {
+							unsafe { counter.inc_unsync() }
+						});
+					}
+				});
+			}
+		});
+		assert_eq!(counter.get(), 80);
+	}
+
+	#[test]
+	fn test_sync_on_same_stripe() {
+		let counters = [Counter::new(), Counter::new()];
+
+		// Both objects fall into the only stripe, the nested block does not wait for itself.
+		let result = sync!(on &counters[0], ->SHARED_SYNC {
+			sync!(on &counters[1], ->SHARED_SYNC {
+				sync!(on &counters[0], ->SHARED_SYNC { 1 + 2 })
+			})
+		});
+		assert_eq!(result, 3);
+
+		// The stripe is still released and other threads are excluded.
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				let counter = &counters[i % 2];
+				scope.spawn(move || {
+					for _ in 0..10 {
+						sync!(on counter, ->SHARED_SYNC {
+							unsafe { counter.inc_unsync() }
+						});
+					}
+				});
+			}
+		});
+		for counter in &counters {
+			assert_eq!(counter.get(), 40);
+		}
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_on_object {
+	use std::sync::Arc;
+	use std::sync::atomic::AtomicUsize;
+	use std::sync::atomic::Ordering;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(SHARED_SYNC: striped<1>);
+
+	#[tokio::test]
+	async fn test_sync_on_same_stripe() {
+		let objects = [0u8, 1];
+
+		let result = sync!(on &objects[0], ->SHARED_SYNC {
+			sync!(on &objects[1], ->SHARED_SYNC {
+				tokio::task::yield_now().await;
+
+				1 + 2
+			})
+		});
+		assert_eq!(result, 3);
+
+		let inside = Arc::new(AtomicUsize::new(0));
+		let tasks: Vec<_> = (0..4)
+			.map(|i| {
+				let inside = inside.clone();
+				tokio::spawn(async move {
+					let object = Box::new(i);
+					sync!(on &object, ->SHARED_SYNC {
+						sync!(on &inside, ->SHARED_SYNC {
+							assert_eq!(inside.fetch_add(1, Ordering::SeqCst), 0);
+							tokio::task::yield_now().await;
+							inside.fetch_sub(1, Ordering::SeqCst);
+						})
+					})
+				})
+			})
+			.collect();
+		for task in tasks {
+			task.await.unwrap();
+		}
+	}
+}
+
+#[cfg(all(test, feature = "attr", feature = "point", not(feature = "async")))]
+mod test_attr_fn {
+	use std::time::Duration;
+	use synchronized::sync_point;
+	use synchronized::synchronized;
+
+	sync_point!(COUNT_SYNC);
+
+	static mut COUNT: usize = 0;
+
+	#[synchronized(COUNT_SYNC)]
+	fn inc(skip: bool) -> Result<usize, ()> {
+		if skip {
+			return Err(());
+		}
+
+		let count = unsafe { COUNT };
+		std::thread::sleep(Duration::from_micros(10));
+		unsafe {
+			COUNT = count + 1;
+		}
+
+		Ok(count + 1)
+	}
+
+	#[synchronized(COUNT_SYNC)]
+	fn try_inc(skip: bool) -> Result<usize, ()> {
+		let count = inc_unsync(skip)?;
+
+		Ok(count)
+	}
+
+	fn inc_unsync(skip: bool) -> Result<usize, ()> {
+		if skip {
+			return Err(());
+		}
+
+		unsafe {
+			COUNT += 1;
+			Ok(COUNT)
+		}
+	}
+
+	#[synchronized]
+	fn anonymous(mut value: usize) -> usize {
+		value += 1;
+		value
+	}
+
+	#[synchronized(COUNT_SYNC)]
+	fn factorial(value: usize) -> usize {
+		// Calls itself without waiting for itself.
+		match value {
+			0 => 1,
+			_ => value * factorial(value - 1),
+		}
+	}
+
+	#[synchronized]
+	fn evens(len: usize) -> Option<impl Iterator<Item = usize>> {
+		Some((0..len).filter(|a| a % 2 == 0))
+	}
+
+	mod reexport {
+		pub use synchronized as sync_lib;
+	}
+
+	#[synchronized(COUNT_SYNC, crate = reexport::sync_lib)]
+	fn count() -> usize {
+		unsafe { COUNT }
+	}
+
+	#[test]
+	fn test_synchronized_fn() {
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				scope.spawn(move || {
+					for _ in 0..10 {
+						// The lock is released after `return` and `?`.
+						let _e = inc(i % 2 == 0);
+						let _e = try_inc(i % 2 == 0);
+						let _e = inc(false);
+					}
+				});
+			}
+		});
+
+		assert_eq!(count(), 160);
+		assert_eq!(anonymous(1), 2);
+		assert_eq!(factorial(5), 120);
+		assert_eq!(evens(5).map(|a| a.collect()), Some(vec![0, 2, 4]));
+	}
+}
+
+#[cfg(all(test, feature = "attr", not(feature = "async")))]
+mod test_attr_impl {
+	use std::cell::UnsafeCell;
+	use std::sync::mpsc;
+	use std::time::Duration;
+	use synchronized::synchronized;
+
+	struct Counter {
+		count: UnsafeCell<usize>,
+	}
+
+	unsafe impl Sync for Counter {}
+
+	#[synchronized(instance)]
+	impl Counter {
+		fn new() -> Self {
+			Self {
+				count: UnsafeCell::new(0),
+			}
+		}
+
+		fn inc(&self) {
+			unsafe {
+				let count = *self.count.get();
+				std::thread::sleep(Duration::from_micros(10));
+				*self.count.get() = count + 1;
+			}
+		}
+
+		fn into_count(self) -> usize {
+			self.count.into_inner()
+		}
+	}
+
+	static mut TOTAL: usize = 0;
+
+	struct Total;
+
+	#[synchronized]
+	impl Total {
+		fn add(&self, value: usize) {
+			unsafe {
+				let total = TOTAL;
+				std::thread::sleep(Duration::from_micros(10));
+				TOTAL = total + value;
+			}
+		}
+
+		fn sub(&self, value: usize) {
+			unsafe {
+				let total = TOTAL;
+				std::thread::sleep(Duration::from_micros(10));
+				TOTAL = total - value;
+			}
+		}
+
+		fn add_sub(&self, add: usize, sub: usize) {
+			// Synchronized methods do not wait for the monitor held by this one.
+			self.add(add);
+			self.sub(sub);
+		}
+
+		#[synchronized(skip)]
+		fn get(&self) -> usize {
+			// Reading without synchronization.
+			unsafe { TOTAL }
+		}
+	}
+
+	#[synchronized(instance)]
+	impl Counter {
+		fn inc_twice(&self) {
+			self.inc();
+			self.inc();
+		}
+	}
+
+	// Shares the monitor of `Total` with the block above.
+	#[synchronized]
+	impl Total {
+		fn add_twice(&self, value: usize) {
+			unsafe {
+				let total = TOTAL;
+				std::thread::sleep(Duration::from_micros(10));
+				TOTAL = total + value;
+			}
+			self.add(value);
+		}
+	}
+
+	struct Generic<T>(T);
+
+	#[synchronized]
+	impl<T: 'static> Generic<T> {
+		fn notify(&self, sender: mpsc::Sender<()>) {
+			sender.send(()).unwrap();
+		}
+	}
+
+	#[synchronized]
+	impl<T: 'static> Generic<T> {
+		fn notify_other(&self) -> bool {
+			// Generic<u16> has its own monitor, not held by this method.
+			let (sender, receiver) = mpsc::channel();
+			std::thread::spawn(move || Generic(0u16).notify(sender));
+
+			receiver.recv_timeout(Duration::from_secs(10)).is_ok()
+		}
+	}
+
+	#[test]
+	fn test_synchronized_impl() {
+		let counters = [Counter::new(), Counter::new()];
+
+		std::thread::scope(|scope| {
+			for i in 0..8 {
+				let counter = &counters[i % 2];
+				scope.spawn(move || {
+					for _ in 0..10 {
+						counter.inc();
+						counter.inc_twice();
+						Total.add(2);
+						Total.sub(1);
+						Total.add_sub(3, 2);
+						Total.add_twice(1);
+					}
+				});
+			}
+		});
+
+		assert_eq!(counters.map(Counter::into_count), [120, 120]);
+		assert_eq!(Total.get(), 320);
+	}
+
+	#[test]
+	fn test_generic_impl() {
+		assert!(Generic(0u8).notify_other());
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_wait {
+	use synchronized::sync;
+	use synchronized::sync_notify;
+	use synchronized::sync_point;
+	use synchronized::sync_wait;
+
+	sync_point!(QUEUE_SYNC: (Vec<usize>, bool) = (Vec::new(), false));
+
+	#[test]
+	fn test_sync_wait() {
+		let consumers: Vec<_> = (0..4)
+			.map(|_| {
+				std::thread::spawn(|| {
+					let mut sum = 0;
+					loop {
+						let value = sync_wait!(->QUEUE_SYNC(queue, closed) until !queue.is_empty() || *closed {
+							queue.pop()
+						});
+						match value {
+							Some(a) => sum += a,
+							None => break sum,
+						}
+					}
+				})
+			})
+			.collect();
+
+		for i in 1..=100 {
+			sync!(->QUEUE_SYNC(queue, _closed) {
+				queue.push(i);
+			});
+			sync_notify!(QUEUE_SYNC, one);
+		}
+		sync!(->QUEUE_SYNC(_queue, closed) {
+			*closed = true;
+		});
+		sync_notify!(QUEUE_SYNC, all);
+
+		let sum: usize = consumers.into_iter().map(|a| a.join().unwrap()).sum();
+		assert_eq!(sum, 5050);
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_wait {
+	use synchronized::sync;
+	use synchronized::sync_notify;
+	use synchronized::sync_point;
+	use synchronized::sync_wait;
+
+	sync_point!(QUEUE_SYNC: (Vec<usize>, bool) = (Vec::new(), false));
+
+	#[tokio::test]
+	async fn test_sync_wait() {
+		let consumers: Vec<_> = (0..4)
+			.map(|_| {
+				tokio::spawn(async {
+					let mut sum = 0;
+					loop {
+						let value = sync_wait!(->QUEUE_SYNC(queue, closed) until !queue.is_empty() || *closed {
+							queue.pop()
+						});
+						match value {
+							Some(a) => sum += a,
+							None => break sum,
+						}
+					}
+				})
+			})
+			.collect();
+
+		for i in 1..=100 {
+			sync!(->QUEUE_SYNC(queue, _closed) {
+				queue.push(i);
+			});
+			sync_notify!(QUEUE_SYNC, one);
+			if i % 10 == 0 {
+				// The consumers run on the same thread.
+				tokio::task::yield_now().await;
+			}
+		}
+		sync!(->QUEUE_SYNC(_queue, closed) {
+			*closed = true;
+		});
+		sync_notify!(QUEUE_SYNC, all);
+
+		let mut sum = 0;
+		for consumer in consumers {
+			sum += consumer.await.unwrap();
+		}
+		assert_eq!(sum, 5050);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_rw {
+	use synchronized::sync_point;
+	use synchronized::sync_read;
+	use synchronized::sync_write;
+
+	sync_point! {
+		CONFIG_SYNC: rwlock<(Vec<usize>, usize)> = (Vec::new(), 0);
+		COUNT_SYNC: rwlock<usize> = 0
+	}
+
+	#[test]
+	fn test_sync_read_write() {
+		std::thread::scope(|scope| {
+			for i in 0..4 {
+				scope.spawn(move || {
+					for _ in 0..10 {
+						sync_write!(->CONFIG_SYNC(values, version) {
+							values.push(i);
+							*version += 1;
+						});
+						let (len, version) = sync_read!(->CONFIG_SYNC(values, version) {
+							(values.len(), *version)
+						});
+						assert_eq!(len, version);
+					}
+				});
+			}
+		});
+
+		sync_read!(->CONFIG_SYNC(values, version) {
+			// Readers do not wait for each other.
+			assert!(CONFIG_SYNC.try_read().is_some());
+			assert!(CONFIG_SYNC.try_write().is_none());
+
+			assert_eq!(values.len(), 40);
+			assert_eq!(*version, 40);
+		});
+	}
+
+	#[test]
+	fn test_downgrade() {
+		let count = sync_write!(->COUNT_SYNC(count) {
+			*count += 1;
+			let written = *count;
+			downgrade!();
+
+			// Readers are let in, writers are not.
+			let read = std::thread::spawn(|| sync_read!(->COUNT_SYNC(count) { *count }));
+			assert_eq!(read.join().unwrap(), written);
+			let write = std::thread::spawn(|| COUNT_SYNC.try_write().is_some());
+			assert!(!write.join().unwrap());
+
+			*count
+		});
+		assert_eq!(count, 1);
+		assert!(COUNT_SYNC.try_write().is_some());
+
+		// `downgrade!();` is found after a long part of the block.
+		let count = sync_write!(->COUNT_SYNC(count) {
+			*count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1;
+			*count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1;
+			*count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1;
+			*count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1; *count += 1;
+			downgrade!();
+
+			*count
+		});
+		assert_eq!(count, 33);
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_rw {
+	use synchronized::sync_point;
+	use synchronized::sync_read;
+	use synchronized::sync_write;
+
+	sync_point!(COUNT_SYNC: rwlock<usize> = 0);
+
+	#[tokio::test]
+	async fn test_downgrade() {
+		let count = sync_write!(->COUNT_SYNC(count) {
+			*count += 1;
+			tokio::task::yield_now().await;
+			downgrade!();
+
+			// Readers are let in, writers are not.
+			let read = tokio::spawn(async { sync_read!(->COUNT_SYNC(count) { *count }) });
+			assert_eq!(read.await.unwrap(), 1);
+			assert!(COUNT_SYNC.try_write().is_none());
+
+			*count
+		});
+		assert_eq!(count, 1);
+		assert!(COUNT_SYNC.try_write().is_some());
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_guard {
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(COMB_SYNC: (Vec<usize>, usize) = (Vec::new(), 0));
+
+	fn begin() -> impl core::ops::DerefMut<Target = (Vec<usize>, usize)> {
+		let mut guard = COMB_SYNC.new_lock();
+		guard.0.push(1);
+		guard
+	}
+
+	#[test]
+	fn test_sync_guard() {
+		let mut guard = begin();
+		guard.1 += 1;
+		assert!(COMB_SYNC.try_lock().is_none());
+		drop(guard);
+
+		let guard = COMB_SYNC.new_lock();
+		assert_eq!(*guard, (vec![1], 1));
+		COMB_SYNC.unlock(guard);
+
+		let result = sync!(->COMB_SYNC(values) {
+			values.1
+		});
+		assert_eq!(result, 1);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_closure_api {
+	use synchronized::sync_point;
+
+	sync_point!(COUNT_SYNC: usize = 0);
+
+	trait Counter {
+		fn inc(&self) -> usize;
+	}
+
+	struct Global;
+
+	impl Counter for Global {
+		fn inc(&self) -> usize {
+			COUNT_SYNC.with(|count| {
+				*count += 1;
+				*count
+			})
+		}
+	}
+
+	#[test]
+	fn test_closure_api() {
+		std::thread::scope(|scope| {
+			for _ in 0..8 {
+				scope.spawn(|| {
+					for _ in 0..10 {
+						Global.inc();
+					}
+				});
+			}
+		});
+		assert_eq!(COUNT_SYNC.get_cloned(), 80);
+
+		let lock = COUNT_SYNC.new_lock();
+		assert_eq!(COUNT_SYNC.with_try(|count| *count), None);
+		drop(lock);
+
+		COUNT_SYNC.update(|count| *count *= 2);
+		assert_eq!(COUNT_SYNC.replace(1), 160);
+		assert_eq!(COUNT_SYNC.take(), 1);
+		assert_eq!(COUNT_SYNC.with_try(|count| *count), Some(0));
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_closure_api {
+	use synchronized::sync_point;
+
+	sync_point!(COUNT_SYNC: usize = 0);
+
+	async fn inc() -> usize {
+		COUNT_SYNC
+			.with(|count| {
+				*count += 1;
+				*count
+			})
+			.await
+	}
+
+	#[tokio::test]
+	async fn test_closure_api() {
+		let tasks: Vec<_> = (0..8)
+			.map(|_| {
+				tokio::spawn(async {
+					for _ in 0..10 {
+						inc().await;
+						tokio::task::yield_now().await;
+					}
+				})
+			})
+			.collect();
+		for task in tasks {
+			task.await.unwrap();
+		}
+		assert_eq!(COUNT_SYNC.get_cloned().await, 80);
+
+		let lock = COUNT_SYNC.new_lock().await;
+		assert_eq!(COUNT_SYNC.with_try(|count| *count), None);
+		drop(lock);
+
+		COUNT_SYNC.update(|count| *count *= 2).await;
+		assert_eq!(COUNT_SYNC.replace(1).await, 160);
+		assert_eq!(COUNT_SYNC.take().await, 1);
+		assert_eq!(COUNT_SYNC.with_try(|count| *count), Some(0));
+	}
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod test_transactional {
+	use synchronized::sync;
+
+	fn add(value: usize) -> usize {
+		sync!(transactional (sum: usize = 0, history: Vec<usize> = Vec::new()) {
+			history.push(value);
+			*sum += value;
+			if value > 10 {
+				panic!("too large");
+			}
+
+			*sum
+		})
+	}
+
+	/// Adds its value to the sum when dropped.
+	struct AddOnDrop(usize);
+
+	impl Drop for AddOnDrop {
+		fn drop(&mut self) {
+			add(self.0);
+		}
+	}
+
+	#[test]
+	fn test_sync_transactional() {
+		assert_eq!(add(1), 1);
+		assert!(std::panic::catch_unwind(|| add(100)).is_err());
+		assert_eq!(add(2), 3);
+
+		// A block completed during unwinding is not rolled back.
+		let result = std::panic::catch_unwind(|| {
+			let _add = AddOnDrop(4);
+			panic!("unwinding");
+		});
+		assert!(result.is_err());
+		assert_eq!(add(0), 7);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_poison {
+	use synchronized::core::PoisonPolicy;
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::try_sync;
+
+	sync_point! {
+		IGNORE_SYNC: Vec<usize> = Vec::new();
+
+		#[poison = "propagate"]
+		PROPAGATE_SYNC: Vec<usize> = Vec::new();
+
+		/// Reset to the initial value.
+		#[poison = "reset"]
+		pub RESET_SYNC: Vec<usize> = Vec::new();
+
+		TRY_SYNC: usize = 0;
+
+		TX_IGNORE_SYNC: Vec<usize> = Vec::new();
+
+		#[poison = "propagate"]
+		TX_PROPAGATE_SYNC: Vec<usize> = Vec::new();
+
+		#[poison = "reset"]
+		TX_RESET_SYNC: Vec<usize> = Vec::new()
+	}
+
+	fn push_and_panic() {
+		let _e = std::thread::spawn(|| {
+			sync!(->IGNORE_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("ignore");
+				}
+			});
+		})
+		.join();
+		let _e = std::thread::spawn(|| {
+			sync!(->PROPAGATE_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("propagate");
+				}
+			});
+		})
+		.join();
+		let _e = std::thread::spawn(|| {
+			sync!(->RESET_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("reset");
+				}
+			});
+		})
+		.join();
+	}
+
+	#[test]
+	fn test_poison_policy() {
+		assert_eq!(IGNORE_SYNC.poison_policy(), PoisonPolicy::Ignore);
+		assert_eq!(PROPAGATE_SYNC.poison_policy(), PoisonPolicy::Propagate);
+		assert_eq!(RESET_SYNC.poison_policy(), PoisonPolicy::Reset);
+
+		push_and_panic();
+
+		assert!(IGNORE_SYNC.is_poisoned());
+		assert_eq!(IGNORE_SYNC.get_cloned(), [1]);
+
+		assert!(PROPAGATE_SYNC.lock_checked().is_err());
+		assert!(std::panic::catch_unwind(|| PROPAGATE_SYNC.get_cloned()).is_err());
+		PROPAGATE_SYNC.clear_poison();
+		assert_eq!(PROPAGATE_SYNC.get_cloned(), [1]);
+
+		assert!(RESET_SYNC.is_poisoned());
+		assert_eq!(RESET_SYNC.get_cloned(), []);
+		assert!(!RESET_SYNC.is_poisoned());
+	}
+
+	#[test]
+	fn test_try_lock_poisoned() {
+		let _e = std::thread::spawn(|| {
+			sync!(->TRY_SYNC(count) {
+				*count += 1;
+				if *count > 0 {
+					panic!("try");
+				}
+			});
+		})
+		.join();
+
+		// The sync point is free, even if the synchronization structure is poisoned.
+		assert!(TRY_SYNC.is_poisoned());
+		let result = try_sync!((->TRY_SYNC(count)) {
+			*count += 1;
+
+			*count
+		});
+		assert_eq!(result, Some(2));
+	}
+
+	#[test]
+	fn test_transactional_not_poisoned() {
+		for point in [&TX_IGNORE_SYNC, &TX_PROPAGATE_SYNC, &TX_RESET_SYNC] {
+			point.update(|values| values.push(2));
+		}
+
+		let _e = std::thread::spawn(|| {
+			sync!(transactional ->TX_IGNORE_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("ignore");
+				}
+			});
+		})
+		.join();
+		let _e = std::thread::spawn(|| {
+			sync!(transactional ->TX_PROPAGATE_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("propagate");
+				}
+			});
+		})
+		.join();
+		let _e = std::thread::spawn(|| {
+			sync!(transactional ->TX_RESET_SYNC(values) {
+				values.push(1);
+				if !values.is_empty() {
+					panic!("reset");
+				}
+			});
+		})
+		.join();
+
+		// The values are restored, not reset, and can be locked as usual.
+		for point in [&TX_IGNORE_SYNC, &TX_PROPAGATE_SYNC, &TX_RESET_SYNC] {
+			assert!(!point.is_poisoned());
+			assert_eq!(*point.lock_checked().unwrap(), [2]);
+		}
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_reset {
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::sync_reset;
+
+	sync_point! {
+		COUNT_SYNC: usize = 10;
+		NAME_SYNC: &'static str = "first"
+	}
+
+	#[test]
+	fn test_reset() {
+		sync!((->COUNT_SYNC(count), ->NAME_SYNC(name)) {
+			*count += 1;
+			*name = "second";
+		});
+		sync_reset!(COUNT_SYNC);
+		assert_eq!(COUNT_SYNC.get_cloned(), 10);
+		assert_eq!(NAME_SYNC.get_cloned(), "second");
+	}
+}
+
+#[cfg(all(test, not(feature = "async")))]
+mod test_local_point {
+	use synchronized::core::SyncPoint;
+	use synchronized::sync;
+
+	#[cfg(any(feature = "std", not(feature = "pl")))]
+	use synchronized::beh::std::Mutex;
+
+	#[cfg(all(feature = "pl", not(feature = "std")))]
+	use synchronized::beh::pl::Mutex;
+
+	#[test]
+	fn test_sync_local_point() {
+		// A sync point that is not `static` is not registered for `reset_all`.
+		let point = SyncPoint::new(Mutex::new(0));
+		let count = sync!(->point(count) {
+			*count += 1;
+
+			*count
+		});
+		assert_eq!(count, 1);
+		assert_eq!(point.try_lock().map(|a| *a), Some(1));
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	not(feature = "async"),
+	any(debug_assertions, feature = "debug-checks")
+))]
+mod test_reentry {
+	use std::time::Duration;
+	use synchronized::core::CancelToken;
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::sync_read;
+	use synchronized::sync_write;
+
+	sync_point!(REENTRY_SYNC: usize = 0);
+
+	#[test]
+	fn test_reentry() {
+		let result = std::thread::spawn(|| {
+			sync!(->REENTRY_SYNC(count) {
+				*count += 1;
+
+				sync!(->REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("re-entered REENTRY_SYNC at tests/macro.rs:"));
+		assert!(message.contains("while holding it from tests/macro.rs:"));
+
+		// The sync point is released after the panic.
+		assert_eq!(REENTRY_SYNC.get_cloned(), 1);
+	}
+
+	#[test]
+	fn test_reentry_timeout() {
+		sync_point!(TIMEOUT_REENTRY_SYNC: usize = 0);
+
+		let result = std::thread::spawn(|| {
+			sync!(->TIMEOUT_REENTRY_SYNC(count) {
+				*count += 1;
+
+				// Would time out instead of locking the sync point.
+				let _ = sync!(timeout = Duration::from_secs(10), ->TIMEOUT_REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("re-entered TIMEOUT_REENTRY_SYNC at tests/macro.rs:"));
+		assert_eq!(TIMEOUT_REENTRY_SYNC.get_cloned(), 1);
+	}
+
+	#[test]
+	fn test_reentry_cancel() {
+		sync_point!(CANCEL_REENTRY_SYNC: usize = 0);
+
+		let result = std::thread::spawn(|| {
+			let token = CancelToken::new();
+			sync!(->CANCEL_REENTRY_SYNC(count) {
+				*count += 1;
+
+				// Would wait until the token is cancelled.
+				let _ = sync!(cancel = &token, ->CANCEL_REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("re-entered CANCEL_REENTRY_SYNC at tests/macro.rs:"));
+		assert_eq!(CANCEL_REENTRY_SYNC.get_cloned(), 1);
+	}
+
+	#[test]
+	fn test_rw_reentry() {
+		sync_point!(RW_REENTRY_SYNC: rwlock<usize> = 0);
+
+		let result = std::thread::spawn(|| {
+			sync_write!(->RW_REENTRY_SYNC(count) {
+				*count += 1;
+
+				sync_write!(->RW_REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("re-entered RW_REENTRY_SYNC at tests/macro.rs:"));
+		assert!(message.contains("while holding it from tests/macro.rs:"));
+
+		// Reading while holding the exclusive lock would also wait forever.
+		let result = std::thread::spawn(|| {
+			sync_write!(->RW_REENTRY_SYNC(count) {
+				*count += 1;
+
+				sync_read!(->RW_REENTRY_SYNC(count) { *count })
+			})
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("re-entered RW_REENTRY_SYNC at tests/macro.rs:"));
+
+		// The sync point is released after the panics, a downgraded lock is not tracked.
+		let count = sync_write!(->RW_REENTRY_SYNC(count) {
+			*count += 1;
+			downgrade!();
+
+			sync_read!(->RW_REENTRY_SYNC(count) { *count })
+		});
+		assert_eq!(count, 3);
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl"),
+	any(debug_assertions, feature = "debug-checks")
+))]
+mod test_async_reentry {
+	use std::time::Duration;
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::sync_read;
+	use synchronized::sync_write;
+
+	sync_point! {
+		REENTRY_SYNC: usize = 0;
+		TIMEOUT_REENTRY_SYNC: usize = 0;
+		OTHER_TASK_SYNC: usize = 0
+	}
+
+	#[tokio::test]
+	async fn test_reentry() {
+		let result = tokio::spawn(async {
+			sync!(->REENTRY_SYNC(count) {
+				*count += 1;
+
+				sync!(->REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.await;
+
+		let message = result
+			.unwrap_err()
+			.into_panic()
+			.downcast::<String>()
+			.unwrap();
+		assert!(message.contains("re-entered REENTRY_SYNC at tests/macro.rs:"));
+		assert!(message.contains("while holding it from tests/macro.rs:"));
+		assert_eq!(REENTRY_SYNC.get_cloned().await, 1);
+
+		let result = tokio::spawn(async {
+			sync!(->TIMEOUT_REENTRY_SYNC(count) {
+				*count += 1;
+
+				let _ = sync!(timeout = Duration::from_secs(10), ->TIMEOUT_REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.await;
+
+		let message = result
+			.unwrap_err()
+			.into_panic()
+			.downcast::<String>()
+			.unwrap();
+		assert!(message.contains("re-entered TIMEOUT_REENTRY_SYNC at tests/macro.rs:"));
+		assert_eq!(TIMEOUT_REENTRY_SYNC.get_cloned().await, 1);
+	}
+
+	#[tokio::test]
+	async fn test_rw_reentry() {
+		sync_point!(RW_REENTRY_SYNC: rwlock<usize> = 0);
+
+		let result = tokio::spawn(async {
+			sync_write!(->RW_REENTRY_SYNC(count) {
+				*count += 1;
+
+				sync_write!(->RW_REENTRY_SYNC(count) {
+					*count += 1;
+				});
+			});
+		})
+		.await;
+
+		let message = result
+			.unwrap_err()
+			.into_panic()
+			.downcast::<String>()
+			.unwrap();
+		assert!(message.contains("re-entered RW_REENTRY_SYNC at tests/macro.rs:"));
+		assert!(message.contains("while holding it from tests/macro.rs:"));
+		assert_eq!(sync_read!(->RW_REENTRY_SYNC(count) { *count }), 1);
+	}
+
+	#[tokio::test]
+	async fn test_other_task() {
+		// Another task waits for the sync point held by the current one.
+		let task = sync!(->OTHER_TASK_SYNC(count) {
+			let task = tokio::spawn(async {
+				sync!(->OTHER_TASK_SYNC(count) {
+					*count += 1;
+				})
+			});
+			tokio::time::sleep(Duration::from_millis(10)).await;
+			*count += 1;
+
+			task
+		});
+		task.await.unwrap();
+		assert_eq!(OTHER_TASK_SYNC.get_cloned().await, 2);
+	}
+}
+
+#[cfg(all(test, feature = "point", feature = "lockdep", not(feature = "async")))]
+mod test_lockdep {
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point! {
+		FIRST_SYNC: usize = 0;
+		SECOND_SYNC: usize = 0
+	}
+
+	#[test]
+	fn test_lock_order_inversion() {
+		sync!(->FIRST_SYNC(first) {
+			sync!(->SECOND_SYNC(second) {
+				*first += 1;
+				*second += 1;
+			});
+		});
+
+		// The reverse order is reported without an actual deadlock.
+		let result = std::thread::spawn(|| {
+			sync!(->SECOND_SYNC(second) {
+				sync!(->FIRST_SYNC(first) {
+					*first += 1;
+					*second += 1;
+				});
+			});
+		})
+		.join();
+
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.starts_with("lock order inversion of SECOND_SYNC and FIRST_SYNC"));
+		assert!(message.contains("but earlier: FIRST_SYNC (locked at tests/macro.rs:"));
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "pl-deadlock-detection",
+	not(feature = "std"),
+	not(feature = "async")
+))]
+mod test_deadlock {
+	use std::sync::Barrier;
+	use std::time::Duration;
+	use std::time::Instant;
+	use synchronized::beh::pl::Mutex;
+	use synchronized::core::SyncPoint;
+	use synchronized::deadlock;
+
+	static BARRIER: Barrier = Barrier::new(2);
+
+	/// A named sync point without the initial value, it is not registered
+	/// for `reset_all`, which would wait for it forever.
+	fn leaked_point(name: &'static str) -> &'static SyncPoint<Mutex<usize>> {
+		Box::leak(Box::new(SyncPoint::new(Mutex::new(0)).with_name(name)))
+	}
+
+	#[test]
+	fn test_deadlock_report() {
+		let left_sync = leaked_point("LEFT_SYNC");
+		let right_sync = leaked_point("RIGHT_SYNC");
+
+		// The threads deadlock each other and are never finished.
+		std::thread::spawn(move || {
+			let mut left = left_sync.new_lock();
+			BARRIER.wait();
+			*left += *right_sync.new_lock();
+		});
+		std::thread::spawn(move || {
+			let mut right = right_sync.new_lock();
+			BARRIER.wait();
+			*right += *left_sync.new_lock();
+		});
+
+		let start = Instant::now();
+		let reports = loop {
+			let reports = deadlock::check();
+			if !reports.is_empty() || start.elapsed() > Duration::from_secs(10) {
+				break reports;
+			}
+
+			std::thread::sleep(Duration::from_millis(10));
+		};
+
+		assert_eq!(reports.len(), 1);
+		let threads = &reports[0].threads;
+		assert_eq!(threads.len(), 2);
+		for thread in threads {
+			let waiting = thread.waiting.unwrap();
+			assert_eq!(thread.held.len(), 1);
+			assert_ne!(waiting.name, thread.held[0].name);
+			assert!(waiting.location.file().ends_with("macro.rs"));
+		}
+		assert!(reports[0].to_string().contains("waits for"));
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "track-holders",
+	not(feature = "async")
+))]
+mod test_holders {
+	use std::sync::mpsc;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(HELD_SYNC: usize = 0);
+
+	#[test]
+	fn test_holder() {
+		assert!(HELD_SYNC.holder().is_none());
+
+		let (locked_tx, locked_rx) = mpsc::channel();
+		let (release_tx, release_rx) = mpsc::channel::<()>();
+		let thread = std::thread::Builder::new()
+			.name(String::from("holder"))
+			.spawn(move || {
+				sync!(->HELD_SYNC(count) {
+					*count += 1;
+					locked_tx.send(line!()).unwrap();
+					release_rx.recv().unwrap();
+				});
+			})
+			.unwrap();
+
+		let line = locked_rx.recv().unwrap();
+		let holder = HELD_SYNC.holder().unwrap();
+		assert_eq!(holder.thread_name.as_deref(), Some("holder"));
+		assert_eq!(holder.location.file(), file!());
+		assert!(holder.location.line() < line);
+
+		let held = synchronized::dump_holders();
+		assert!(held.iter().any(|a| a.name == "HELD_SYNC"));
+
+		release_tx.send(()).unwrap();
+		thread.join().unwrap();
+		assert!(HELD_SYNC.holder().is_none());
+	}
+
+	#[cfg(feature = "attr")]
+	#[synchronized::synchronized(HELD_SYNC)]
+	fn held() -> (u32, Option<synchronized::core::Holder>) {
+		(line!() - 1, HELD_SYNC.holder())
+	}
+
+	#[cfg(feature = "attr")]
+	#[test]
+	fn test_holder_attr() {
+		// The sync point is locked at the function, not at the attribute.
+		let (line, holder) = held();
+		let holder = holder.unwrap();
+		assert_eq!(holder.location.file(), file!());
+		assert_eq!(holder.location.line(), line);
+	}
+}
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_max_hold {
+	use std::sync::Mutex;
+	use std::time::Duration;
+	use synchronized::core::HoldOverrun;
+	use synchronized::core::set_hold_overrun_callback;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point! {
+		#[max_hold = "1ms"]
+		SLOW_SYNC: usize = 0;
+
+		#[max_hold = "10s"]
+		FAST_SYNC: usize = 0
+	}
+
+	static OVERRUNS: Mutex<Vec<HoldOverrun>> = Mutex::new(Vec::new());
+
+	#[test]
+	fn test_max_hold() {
+		assert_eq!(SLOW_SYNC.max_hold(), Some(Duration::from_millis(1)));
+		set_hold_overrun_callback(|overrun| OVERRUNS.lock().unwrap().push(*overrun));
+
+		let line = line!() + 1;
+		sync!(->SLOW_SYNC(count) {
+			std::thread::sleep(Duration::from_millis(10));
+			*count += 1;
+		});
+		sync!(->FAST_SYNC(count) {
+			*count += 1;
+		});
+
+		let overruns = OVERRUNS.lock().unwrap();
+		assert_eq!(overruns.len(), 1);
+		assert_eq!(overruns[0].name, "SLOW_SYNC");
+		assert_eq!(overruns[0].location.line(), line);
+		assert!(overruns[0].held > overruns[0].max_hold);
+		drop(overruns);
+
+		// The callback replaces itself.
+		set_hold_overrun_callback(|overrun| {
+			OVERRUNS.lock().unwrap().push(*overrun);
+			set_hold_overrun_callback(|_| {});
+		});
+		for _ in 0..2 {
+			sync!(->SLOW_SYNC(count) {
+				std::thread::sleep(Duration::from_millis(10));
+				*count += 1;
+			});
+		}
+		assert_eq!(OVERRUNS.lock().unwrap().len(), 2);
+	}
+}
+
+#[cfg(all(test, feature = "point", feature = "metrics", not(feature = "async")))]
+mod test_metrics {
+	use std::sync::Arc;
+	use std::sync::Barrier;
+	use std::time::Duration;
+	use synchronized::core::MAX_STATS_SITES;
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point!(COUNT_SYNC: usize = 0);
+
+	#[test]
+	fn test_metrics() {
+		let first_line = line!() + 2;
+		for _ in 0..2 {
+			sync!(->COUNT_SYNC(count) {
+				*count += 1;
+			});
+		}
+		let second_line = line!() + 1;
+		sync!(->COUNT_SYNC(count) {
+			std::thread::sleep(Duration::from_millis(5));
+			*count += 1;
+		});
+
+		let barrier = Arc::new(Barrier::new(2));
+		let thread = {
+			let barrier = barrier.clone();
+			std::thread::spawn(move || {
+				let guard = COUNT_SYNC.new_lock();
+				barrier.wait();
+				std::thread::sleep(Duration::from_millis(20));
+				drop(guard);
+			})
+		};
+		barrier.wait();
+		assert!(COUNT_SYNC.try_lock().is_none());
+		let third_line = line!() + 1;
+		sync!(->COUNT_SYNC(count) {
+			*count += 1;
+		});
+		thread.join().unwrap();
+
+		let stats = COUNT_SYNC.stats();
+		assert_eq!(stats.total.acquisitions, 5);
+		assert_eq!(stats.total.contended, 1);
+		assert_eq!(stats.total.try_lock_failures, 1);
+		assert!(stats.total.max_wait > Duration::ZERO);
+		assert!(stats.total.max_hold >= Duration::from_millis(5));
+		assert!(stats.total.total_hold >= stats.total.max_hold);
+		assert_eq!(stats.sites.len(), 5);
+
+		let site = |line| {
+			stats
+				.sites
+				.iter()
+				.find(|a| a.location.line() == line)
+				.map(|a| a.stats)
+				.unwrap()
+		};
+		assert_eq!(site(first_line).acquisitions, 2);
+		assert!(site(second_line).max_hold >= Duration::from_millis(5));
+		assert_eq!(site(third_line).contended, 1);
+
+		COUNT_SYNC.reset_stats();
+		let stats = COUNT_SYNC.stats();
+		assert_eq!(stats.total.acquisitions, 0);
+		assert!(stats.sites.is_empty());
+
+		sync!(->COUNT_SYNC(count) {
+			*count += 1;
+		});
+		let stats = COUNT_SYNC.stats();
+		assert_eq!(stats.total.acquisitions, 1);
+		assert_eq!(stats.sites.len(), 1);
+	}
+
+	#[test]
+	fn test_max_sites() {
+		sync_point!(SITES_SYNC: usize = 0);
+
+		// One place more than the counted ones.
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+		drop(SITES_SYNC.new_lock());
+
+		let stats = SITES_SYNC.stats();
+		assert_eq!(stats.total.acquisitions, MAX_STATS_SITES as u64 + 1);
+		assert_eq!(stats.sites.len(), MAX_STATS_SITES);
+		assert!(stats.sites.iter().all(|a| a.stats.acquisitions == 1));
+	}
+}
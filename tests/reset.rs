@@ -0,0 +1,66 @@
+// `reset_all` resets the sync points of all tests of the process,
+// so it is tested in its own test binary.
+
+#[cfg(all(test, feature = "point", not(feature = "async")))]
+mod test_reset_all {
+	use synchronized::sync;
+	use synchronized::sync_point;
+
+	sync_point! {
+		COUNT_SYNC: usize = 10;
+		NAME_SYNC: &'static str = "first";
+		LOG_SYNC: Vec<&'static str> = Vec::new()
+	}
+
+	#[test]
+	fn test_reset_all() {
+		sync!((->COUNT_SYNC(count), ->NAME_SYNC(name)) {
+			*count += 1;
+			*name = "second";
+		});
+		// Used only through the closure API.
+		LOG_SYNC.update(|log| log.push("first"));
+
+		synchronized::core::reset_all();
+		assert_eq!(COUNT_SYNC.get_cloned(), 10);
+		assert_eq!(NAME_SYNC.get_cloned(), "first");
+		assert_eq!(LOG_SYNC.get_cloned(), Vec::<&str>::new());
+	}
+}
+
+#[cfg(all(
+	test,
+	feature = "point",
+	feature = "async",
+	not(feature = "std"),
+	not(feature = "pl")
+))]
+mod test_async_reset_all {
+	use synchronized::sync;
+	use synchronized::sync_point;
+	use synchronized::sync_reset;
+
+	sync_point! {
+		COUNT_SYNC: usize = 10;
+		LOG_SYNC: Vec<&'static str> = Vec::new()
+	}
+
+	#[tokio::test]
+	async fn test_reset_all() {
+		sync!(->COUNT_SYNC(count) {
+			*count += 1;
+		});
+		sync_reset!(COUNT_SYNC);
+		assert_eq!(COUNT_SYNC.get_cloned().await, 10);
+
+		sync!(->COUNT_SYNC(count) {
+			*count += 1;
+		});
+		// Used only through the closure API.
+		LOG_SYNC.update(|log| log.push("first")).await;
+
+		synchronized::core::reset_all().await;
+		assert_eq!(COUNT_SYNC.get_cloned().await, 10);
+		assert_eq!(LOG_SYNC.get_cloned().await, Vec::<&str>::new());
+	}
+}